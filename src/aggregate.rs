@@ -0,0 +1,285 @@
+use crate::condition::Condition;
+use crate::expression::Expression;
+use crate::fragment::Fragment;
+use crate::prelude::Ordering;
+use crate::window::{over, over_named, Window};
+
+/// An aggregate function call carrying its own parameters
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::{Aggregate, Condition, Expression, SelectBuilder};
+/// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithGroupBy};
+///
+/// let mut builder = SelectBuilder::new("orders");
+/// builder.select("customer_id");
+/// builder.select(Aggregate::count_all().filter(Condition::eq("status", "paid".to_string())));
+/// builder.group_by("customer_id");
+///
+/// assert_eq!(
+///     builder.get_query(),
+///     "SELECT customer_id, count(*) FILTER (WHERE status = $1) FROM orders GROUP BY customer_id"
+/// );
+/// ```
+pub struct Aggregate {
+    name: String,
+    distinct: bool,
+    arguments: Vec<Fragment>,
+    order: Vec<Ordering>,
+    filters: Vec<Fragment>,
+}
+
+impl Aggregate {
+    /// Create an aggregate call, merging the parameters of its arguments
+    pub fn new(name: &str, arguments: Vec<Expression>) -> Self {
+        let arguments = arguments
+            .into_iter()
+            .map(Expression::into_fragment)
+            .collect();
+        Aggregate {
+            name: name.into(),
+            distinct: false,
+            arguments,
+            order: vec![],
+            filters: vec![],
+        }
+    }
+
+    /// Count all the rows
+    pub fn count_all() -> CountAll {
+        CountAll {
+            aggregate: Aggregate::new("count", vec![Expression::raw("*")]),
+        }
+    }
+
+    /// Count the rows where an expression is not null
+    pub fn count(expression: Expression) -> Self {
+        Aggregate::new("count", vec![expression])
+    }
+
+    /// Sum the values of an expression
+    pub fn sum(expression: Expression) -> Self {
+        Aggregate::new("sum", vec![expression])
+    }
+
+    /// Average the values of an expression
+    pub fn avg(expression: Expression) -> Self {
+        Aggregate::new("avg", vec![expression])
+    }
+
+    /// Get the minimum value of an expression
+    pub fn min(expression: Expression) -> Self {
+        Aggregate::new("min", vec![expression])
+    }
+
+    /// Get the maximum value of an expression
+    pub fn max(expression: Expression) -> Self {
+        Aggregate::new("max", vec![expression])
+    }
+
+    /// Only aggregate the distinct values of the arguments
+    pub fn distinct(mut self) -> Self {
+        self.distinct = true;
+        self
+    }
+
+    /// Add an order to the aggregated values
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Aggregate, Expression};
+    /// use postgres_querybuilder::prelude::Order;
+    ///
+    /// let expression: Expression = Aggregate::new(
+    ///     "string_agg",
+    ///     vec![Expression::column("name"), Expression::literal(", ")],
+    /// )
+    /// .distinct()
+    /// .order_by(Order::Asc("name".into()))
+    /// .into();
+    ///
+    /// assert_eq!(
+    ///     expression.get_query(),
+    ///     "string_agg(DISTINCT name, ', ' ORDER BY name ASC)"
+    /// );
+    /// ```
+    pub fn order_by<O: Into<Ordering>>(mut self, field: O) -> Self {
+        self.order.push(field.into());
+        self
+    }
+
+    /// Only aggregate the rows matching a condition, joined with `AND`
+    pub fn filter(mut self, condition: Condition) -> Self {
+        self.filters.push(condition.into_operand());
+        self
+    }
+
+    fn into_fragment(self) -> Fragment {
+        let mut call = Fragment::join(self.arguments, ", ");
+        if self.distinct {
+            call = call.wrap("DISTINCT ", "");
+        }
+        if !self.order.is_empty() {
+            let order = self
+                .order
+                .into_iter()
+                .map(Ordering::into_fragment)
+                .collect();
+            call.push(Fragment::join(order, ", ").wrap(" ORDER BY ", ""));
+        }
+        let mut call = call.wrap(format!("{}(", self.name).as_str(), ")");
+        if !self.filters.is_empty() {
+            call.push(Fragment::join(self.filters, " AND ").wrap(" FILTER (WHERE ", ")"));
+        }
+        call
+    }
+
+    /// Compute the aggregate over a window
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Aggregate, Expression, FrameBound, Window};
+    /// use postgres_querybuilder::prelude::Order;
+    ///
+    /// let expression = Aggregate::sum(Expression::column("amount")).over(
+    ///     Window::new()
+    ///         .partition_by("account_id")
+    ///         .order_by(Order::Asc("day".into()))
+    ///         .rows(FrameBound::UnboundedPreceding, FrameBound::CurrentRow),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     expression.get_query(),
+    ///     "sum(amount) OVER (PARTITION BY account_id ORDER BY day ASC ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW)"
+    /// );
+    /// ```
+    pub fn over(self, window: Window) -> Expression {
+        over(self.into_fragment(), window)
+    }
+
+    /// Compute the aggregate over a window of the `WINDOW` clause
+    pub fn over_named(self, name: &str) -> Expression {
+        over_named(self.into_fragment(), name)
+    }
+}
+
+impl From<Aggregate> for Expression {
+    fn from(aggregate: Aggregate) -> Self {
+        Expression::atom(aggregate.into_fragment())
+    }
+}
+
+/// A `count(*)` call, which has no argument to make distinct or to order
+///
+/// ```compile_fail
+/// use postgres_querybuilder::Aggregate;
+///
+/// Aggregate::count_all().distinct();
+/// ```
+pub struct CountAll {
+    aggregate: Aggregate,
+}
+
+impl CountAll {
+    /// Only count the rows matching a condition, joined with `AND`
+    pub fn filter(self, condition: Condition) -> Self {
+        CountAll {
+            aggregate: self.aggregate.filter(condition),
+        }
+    }
+
+    /// Count the rows of a window
+    pub fn over(self, window: Window) -> Expression {
+        self.aggregate.over(window)
+    }
+
+    /// Count the rows of a window of the `WINDOW` clause
+    pub fn over_named(self, name: &str) -> Expression {
+        self.aggregate.over_named(name)
+    }
+}
+
+impl From<CountAll> for Expression {
+    fn from(count: CountAll) -> Self {
+        count.aggregate.into()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::bucket::Bucket;
+    use crate::prelude::Order;
+
+    #[test]
+    fn with_params_everywhere() {
+        let expression = Aggregate::new(
+            "percentile",
+            vec![Expression::column("a"), Expression::param(0.5)],
+        )
+        .order_by(Ordering::asc(
+            Expression::column("b") * Expression::param(2),
+        ))
+        .filter(Condition::gt("c", 3).or(Condition::is_null("c")))
+        .filter(Condition::lt("d", 4))
+        .over(Window::new().partition_by(Expression::param(5)));
+        assert_eq!(
+            expression.get_query(),
+            "percentile(a, $1 ORDER BY b * $2 ASC) FILTER (WHERE (c > $3 OR c IS NULL) AND d < $4) OVER (PARTITION BY $5)"
+        );
+        assert_eq!(expression.get_params().len(), 5);
+    }
+
+    #[test]
+    fn with_raw_arguments() {
+        let mut params = Bucket::new();
+        params.push(5);
+        let expression: Expression = Aggregate::new("max", vec![Expression::raw("a - $1")]).into();
+        assert_eq!(
+            params.merge(expression.get_query().as_str(), expression.get_params()),
+            "max(a - $1)"
+        );
+    }
+
+    #[test]
+    fn with_raw_strings_mixed_with_params() {
+        let mut params = Bucket::new();
+        params.push(5);
+        let expression: Expression =
+            Aggregate::new("max", vec![Expression::raw("a - $1"), Expression::param(2)])
+                .order_by(Ordering::asc(Expression::param(3)))
+                .filter(Condition::raw("b > $1").and(Condition::gt("c", 4)))
+                .into();
+        assert_eq!(
+            expression.merge_into(&mut params),
+            "max(a - $1, $2 ORDER BY $3 ASC) FILTER (WHERE (b > $1) AND c > $4)"
+        );
+        assert_eq!(params.len(), 4);
+    }
+
+    #[test]
+    fn count_all_over_window() {
+        let expression = Aggregate::count_all()
+            .filter(Condition::gt("a", 1))
+            .over(Window::new().partition_by("b"));
+        assert_eq!(
+            expression.get_query(),
+            "count(*) FILTER (WHERE a > $1) OVER (PARTITION BY b)"
+        );
+    }
+
+    #[test]
+    fn with_distinct_over_named_window() {
+        let expression = Aggregate::count(Expression::column("a"))
+            .distinct()
+            .order_by(Order::Desc("a".into()))
+            .over_named("w");
+        assert_eq!(
+            expression.get_query(),
+            "count(DISTINCT a ORDER BY a DESC) OVER w"
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use postgres_types::ToSql;
+use std::ops::Range;
 
 type BucketValue = dyn ToSql + Sync;
 
+#[derive(Default)]
 pub struct Bucket {
   content: Vec<Box<BucketValue>>,
 }
@@ -11,12 +13,8 @@ impl Bucket {
     Bucket { content: vec![] }
   }
 
-  pub fn get_refs(self) -> Vec<&'static BucketValue> {
-    let mut args: Vec<&BucketValue> = vec![];
-    for item in self.content {
-      args.push(Box::leak(item));
-    }
-    args
+  pub fn get_refs(&self) -> Vec<&BucketValue> {
+    self.content.iter().map(|item| item.as_ref()).collect()
   }
 
   pub fn push<T: 'static + ToSql + Sync + Clone>(&mut self, value: T) -> usize {
@@ -24,7 +22,214 @@ impl Bucket {
     self.content.len()
   }
 
+  /// Move the parameters of another bucket at the end of this one
+  ///
+  /// The placeholders of `query`, numbered from `$1` against `other`,
+  /// are shifted so that they point to the merged parameters. When `other`
+  /// is empty, the query is returned unchanged since its placeholders can
+  /// only refer to the parameters of this bucket.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use postgres_querybuilder::bucket::Bucket;
+  ///
+  /// let mut bucket = Bucket::new();
+  /// bucket.push(42);
+  /// let mut other = Bucket::new();
+  /// other.push("rick".to_string());
+  ///
+  /// assert_eq!(bucket.merge("name = $1", other), "name = $2");
+  /// assert_eq!(bucket.merge("id = $1", Bucket::new()), "id = $1");
+  /// assert_eq!(bucket.len(), 2);
+  /// ```
+  pub fn merge(&mut self, query: &str, other: Bucket) -> String {
+    if other.is_empty() {
+      return query.to_string();
+    }
+    let result = shift_placeholders(query, self.content.len());
+    self.content.extend(other.content);
+    result
+  }
+
   pub fn len(&self) -> usize {
     self.content.len()
   }
+
+  pub fn is_empty(&self) -> bool {
+    self.content.is_empty()
+  }
+
+  /// Remove the parameters of the placeholders in `range`
+  ///
+  /// The placeholders following the range must be renumbered with
+  /// `remove_placeholders`.
+  pub(crate) fn remove(&mut self, range: Range<usize>) {
+    self.content.drain(range.start - 1..range.end - 1);
+  }
+}
+
+fn is_identifier_char(c: char) -> bool {
+  c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn read_dollar_quote_tag(chars: &[char], start: usize) -> Option<String> {
+  let mut end = start + 1;
+  while end < chars.len() && chars[end] != '$' {
+    if !(chars[end].is_alphanumeric() || chars[end] == '_') {
+      return None;
+    }
+    end += 1;
+  }
+  if end < chars.len() {
+    Some(chars[start..=end].iter().collect())
+  } else {
+    None
+  }
+}
+
+/// Shift every `$n` placeholder of a query by `offset`
+pub(crate) fn shift_placeholders(query: &str, offset: usize) -> String {
+  if offset == 0 {
+    return query.to_string();
+  }
+  renumber_placeholders(query, |number| number + offset)
+}
+
+/// Renumber the placeholders following a range of removed parameters
+pub(crate) fn remove_placeholders(query: &str, range: &Range<usize>) -> String {
+  if range.is_empty() {
+    return query.to_string();
+  }
+  renumber_placeholders(query, |number| {
+    if number >= range.end {
+      number - range.len()
+    } else {
+      number
+    }
+  })
+}
+
+/// Renumber every `$n` placeholder of a query, leaving quoted strings,
+/// quoted identifiers and dollar quoted strings untouched
+fn renumber_placeholders<F: Fn(usize) -> usize>(query: &str, renumber: F) -> String {
+  let chars: Vec<char> = query.chars().collect();
+  let mut result = String::with_capacity(query.len());
+  let mut index = 0;
+  while index < chars.len() {
+    let current = chars[index];
+    let previous = if index > 0 { Some(chars[index - 1]) } else { None };
+    match current {
+      '\'' | '"' => {
+        let escapable = current == '\''
+          && (previous == Some('e') || previous == Some('E'))
+          && (index < 2 || !is_identifier_char(chars[index - 2]));
+        result.push(current);
+        index += 1;
+        while index < chars.len() {
+          let value = chars[index];
+          result.push(value);
+          index += 1;
+          if escapable && value == '\\' && index < chars.len() {
+            result.push(chars[index]);
+            index += 1;
+          } else if value == current {
+            break;
+          }
+        }
+      }
+      '$' if previous.map(is_identifier_char).unwrap_or(false) => {
+        result.push(current);
+        index += 1;
+      }
+      '$' if index + 1 < chars.len() && chars[index + 1].is_ascii_digit() => {
+        let mut end = index + 1;
+        while end < chars.len() && chars[end].is_ascii_digit() {
+          end += 1;
+        }
+        let number: String = chars[index + 1..end].iter().collect();
+        let number: usize = number.parse().unwrap_or(0);
+        result.push_str(format!("${}", renumber(number)).as_str());
+        index = end;
+      }
+      '$' => match read_dollar_quote_tag(&chars, index) {
+        Some(tag) => {
+          let tag: Vec<char> = tag.chars().collect();
+          let mut end = index + tag.len();
+          while end < chars.len() && !chars[end..].starts_with(&tag) {
+            end += 1;
+          }
+          let end = (end + tag.len()).min(chars.len());
+          result.extend(chars[index..end].iter());
+          index = end;
+        }
+        None => {
+          result.push(current);
+          index += 1;
+        }
+      },
+      _ => {
+        result.push(current);
+        index += 1;
+      }
+    }
+  }
+  result
+}
+
+#[cfg(test)]
+pub mod test {
+  use super::*;
+
+  #[test]
+  fn shift_simple_placeholders() {
+    assert_eq!(
+      shift_placeholders("a = $1 AND b IN ($2, $10)", 3),
+      "a = $4 AND b IN ($5, $13)"
+    );
+  }
+
+  #[test]
+  fn shift_ignores_quoted_content() {
+    assert_eq!(
+      shift_placeholders("a = '$1' AND \"$2\" = $1 AND c = E'\\'$1'", 1),
+      "a = '$1' AND \"$2\" = $2 AND c = E'\\'$1'"
+    );
+    assert_eq!(
+      shift_placeholders("a = 'it''s $1' AND b = $1", 1),
+      "a = 'it''s $1' AND b = $2"
+    );
+  }
+
+  #[test]
+  fn shift_ignores_dollar_quoted_content() {
+    assert_eq!(
+      shift_placeholders("a = $$ $1 $$ AND b = $tag$ $1 $tag$ AND c = $1", 2),
+      "a = $$ $1 $$ AND b = $tag$ $1 $tag$ AND c = $3"
+    );
+  }
+
+  #[test]
+  fn shift_ignores_identifiers() {
+    assert_eq!(shift_placeholders("a$1 = $1", 1), "a$1 = $2");
+  }
+
+  #[test]
+  fn remove_placeholders_after_range() {
+    assert_eq!(
+      remove_placeholders("a = $1 AND b = $4 AND c = '$4'", &(2..4)),
+      "a = $1 AND b = $2 AND c = '$4'"
+    );
+  }
+
+  #[test]
+  fn merge_buckets() {
+    let mut bucket = Bucket::new();
+    bucket.push(1);
+    bucket.push(2);
+    let mut other = Bucket::new();
+    other.push(3);
+    assert_eq!(bucket.merge("value = $1", other), "value = $3");
+    assert_eq!(bucket.len(), 3);
+  }
 }
@@ -0,0 +1,268 @@
+use crate::bucket::Bucket;
+use crate::prelude::*;
+use postgres_types::ToSql;
+
+pub struct CompoundBuilder {
+    query: String,
+    grouped: bool,
+    order: Vec<Ordering>,
+    limit: Option<String>,
+    offset: Option<String>,
+    params: Bucket,
+}
+
+impl CompoundBuilder {
+    /// Create a new compound query starting with a select query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{CompoundBuilder, SelectBuilder};
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithWhere};
+    ///
+    /// let mut admins = SelectBuilder::new("admins");
+    /// admins.select("email");
+    /// let mut users = SelectBuilder::new("users");
+    /// users.select("email");
+    /// users.where_eq("active", true);
+    /// let mut builder = CompoundBuilder::new(admins);
+    /// builder.union(users);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "(SELECT email FROM admins) UNION (SELECT email FROM users WHERE active = $1)"
+    /// );
+    /// ```
+    pub fn new<Q: SelectQuery>(query: Q) -> Self {
+        CompoundBuilder {
+            query: format!("({})", query.get_query()),
+            grouped: true,
+            order: vec![],
+            limit: None,
+            offset: None,
+            params: query.get_params(),
+        }
+    }
+
+    fn combine<Q: SelectQuery>(&mut self, operator: &str, query: Q, intersect: bool) -> &mut Self {
+        // INTERSECT binds tighter than UNION and EXCEPT, so the previous
+        // operations are wrapped to keep them applied from left to right
+        if intersect && !self.grouped {
+            self.query = format!("({})", self.query);
+        }
+        self.grouped = intersect;
+        let subquery = query.get_query();
+        let subquery = self.params.merge(subquery.as_str(), query.get_params());
+        self.query = format!("{} {} ({})", self.query, operator, subquery);
+        self
+    }
+
+    /// Add the distinct rows of a select query
+    pub fn union<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.combine("UNION", query, false)
+    }
+
+    /// Add all the rows of a select query
+    pub fn union_all<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.combine("UNION ALL", query, false)
+    }
+
+    /// Only keep the distinct rows also returned by a select query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{CompoundBuilder, SelectBuilder};
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = CompoundBuilder::new(SelectBuilder::new("a"));
+    /// builder.union(SelectBuilder::new("b"));
+    /// builder.intersect(SelectBuilder::new("c"));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "((SELECT * FROM a) UNION (SELECT * FROM b)) INTERSECT (SELECT * FROM c)"
+    /// );
+    /// ```
+    pub fn intersect<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.combine("INTERSECT", query, true)
+    }
+
+    /// Only keep the rows also returned by a select query, with duplicates
+    pub fn intersect_all<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.combine("INTERSECT ALL", query, true)
+    }
+
+    /// Remove the rows returned by a select query, removing duplicates
+    pub fn except<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.combine("EXCEPT", query, false)
+    }
+
+    /// Remove the rows returned by a select query, keeping duplicates
+    pub fn except_all<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.combine("EXCEPT ALL", query, false)
+    }
+}
+
+impl CompoundBuilder {
+    fn order_by_to_query(&self) -> Option<String> {
+        if !self.order.is_empty() {
+            let result: Vec<String> = self.order.iter().map(|order| order.to_string()).collect();
+            Some(format!("ORDER BY {}", result.join(", ")))
+        } else {
+            None
+        }
+    }
+
+    fn limit_to_query(&self) -> Option<String> {
+        self.limit.as_ref().map(|limit| format!("LIMIT {}", limit))
+    }
+
+    fn offset_to_query(&self) -> Option<String> {
+        self.offset
+            .as_ref()
+            .map(|offset| format!("OFFSET {}", offset))
+    }
+}
+
+impl QueryBuilder for CompoundBuilder {
+    fn add_param<T: 'static + ToSql + Sync + Clone>(&mut self, value: T) -> usize {
+        self.params.push(value)
+    }
+
+    fn merge_params(&mut self, query: &str, params: Bucket) -> String {
+        self.params.merge(query, params)
+    }
+
+    fn get_query(&self) -> String {
+        let mut sections: Vec<String> = vec![self.query.clone()];
+        if let Some(value) = self.order_by_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.limit_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.offset_to_query() {
+            sections.push(value);
+        }
+        sections.join(" ")
+    }
+
+    fn get_params(self) -> Bucket {
+        self.params
+    }
+
+    fn get_ref_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params.get_refs()
+    }
+}
+
+impl SelectQuery for CompoundBuilder {}
+
+impl QueryBuilderWithLimit for CompoundBuilder {
+    fn limit(&mut self, limit: i64) -> &mut Self {
+        let index = self.params.push(limit);
+        self.limit = Some(format!("${}", index));
+        self
+    }
+}
+
+impl QueryBuilderWithOffset for CompoundBuilder {
+    fn offset(&mut self, offset: i64) -> &mut Self {
+        let index = self.params.push(offset);
+        self.offset = Some(format!("${}", index));
+        self
+    }
+}
+
+impl QueryBuilderWithOrder for CompoundBuilder {
+    /// Add order attribute to the combined rows
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{CompoundBuilder, SelectBuilder};
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut builder = CompoundBuilder::new(SelectBuilder::new("admins"));
+    /// builder.union_all(SelectBuilder::new("users"));
+    /// builder.order_by(Order::Asc("email".into()));
+    /// builder.limit(10);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "(SELECT * FROM admins) UNION ALL (SELECT * FROM users) ORDER BY email ASC LIMIT $1"
+    /// );
+    /// ```
+    fn order_by<O: Into<Ordering>>(&mut self, field: O) -> &mut Self {
+        let ordering = field.into().merge_into(&mut self.params);
+        self.order.push(ordering);
+        self
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::select_builder::SelectBuilder;
+
+    fn select(table: &str, value: i32) -> SelectBuilder {
+        let mut builder = SelectBuilder::new(table);
+        builder.select("id");
+        builder.where_eq("value", value);
+        builder
+    }
+
+    #[test]
+    fn with_operators() {
+        let mut builder = CompoundBuilder::new(select("a", 1));
+        builder.union(select("b", 2));
+        builder.union_all(select("c", 3));
+        builder.except(select("d", 4));
+        builder.except_all(select("e", 5));
+        assert_eq!(
+            builder.get_query(),
+            "(SELECT id FROM a WHERE value = $1) UNION (SELECT id FROM b WHERE value = $2) UNION ALL (SELECT id FROM c WHERE value = $3) EXCEPT (SELECT id FROM d WHERE value = $4) EXCEPT ALL (SELECT id FROM e WHERE value = $5)"
+        );
+        assert_eq!(builder.get_params().len(), 5);
+    }
+
+    #[test]
+    fn with_intersect_precedence() {
+        let mut builder = CompoundBuilder::new(select("a", 1));
+        builder.intersect(select("b", 2));
+        builder.intersect_all(select("c", 3));
+        builder.union(select("d", 4));
+        builder.intersect(select("e", 5));
+        assert_eq!(
+            builder.get_query(),
+            "((SELECT id FROM a WHERE value = $1) INTERSECT (SELECT id FROM b WHERE value = $2) INTERSECT ALL (SELECT id FROM c WHERE value = $3) UNION (SELECT id FROM d WHERE value = $4)) INTERSECT (SELECT id FROM e WHERE value = $5)"
+        );
+    }
+
+    #[test]
+    fn with_order_limit_offset() {
+        let mut builder = CompoundBuilder::new(select("a", 1));
+        builder.order_by(Order::Desc("id".into()));
+        builder.limit(10);
+        builder.union(select("b", 2));
+        builder.offset(5);
+        assert_eq!(
+            builder.get_query(),
+            "(SELECT id FROM a WHERE value = $1) UNION (SELECT id FROM b WHERE value = $3) ORDER BY id DESC LIMIT $2 OFFSET $4"
+        );
+    }
+
+    #[test]
+    fn as_subquery() {
+        let mut compound = CompoundBuilder::new(select("a", 1));
+        compound.union(select("b", 2));
+        let mut builder = SelectBuilder::new("c");
+        builder.where_eq("active", true);
+        builder.where_in_select("id", compound);
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM c WHERE active = $1 AND id IN ((SELECT id FROM a WHERE value = $2) UNION (SELECT id FROM b WHERE value = $3))"
+        );
+    }
+}
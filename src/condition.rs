@@ -0,0 +1,740 @@
+use crate::bucket::Bucket;
+use crate::expression::Expression;
+use crate::fragment::Fragment;
+use crate::prelude::SelectQuery;
+use postgres_types::ToSql;
+use std::ops::Not;
+
+#[derive(Clone, PartialEq)]
+enum Kind {
+    Raw,
+    Simple,
+    And,
+    Or,
+}
+
+/// A boolean condition carrying its own parameters
+///
+/// The placeholders of a condition are numbered from `$1` against its own
+/// parameters and are shifted when the condition is added to a builder.
+/// Raw conditions keep their placeholders, referring to the parameters of
+/// the builder, even once combined with other conditions.
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::Condition;
+///
+/// let condition = Condition::eq("name", "rick".to_string())
+///     .or(Condition::eq("name", "morty".to_string()))
+///     .and(!Condition::raw("deleted"));
+///
+/// assert_eq!(
+///     condition.get_query(),
+///     "(name = $1 OR name = $2) AND NOT (deleted)"
+/// );
+/// ```
+pub struct Condition {
+    kind: Kind,
+    fragment: Fragment,
+}
+
+impl Condition {
+    fn new(kind: Kind, query: String, params: Bucket) -> Self {
+        Condition::from_fragment(kind, Fragment::new(query, params))
+    }
+
+    fn from_fragment(kind: Kind, fragment: Fragment) -> Self {
+        Condition { kind, fragment }
+    }
+
+    /// Create a condition from a raw string without parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// let condition = Condition::raw("something IS NULL");
+    ///
+    /// assert_eq!(condition.get_query(), "something IS NULL");
+    /// ```
+    pub fn raw(raw: &str) -> Self {
+        Condition::new(Kind::Raw, raw.into(), Bucket::new())
+    }
+
+    /// Create a condition from a raw string and the parameters it refers to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    /// use postgres_querybuilder::bucket::Bucket;
+    ///
+    /// let mut params = Bucket::new();
+    /// params.push(18);
+    /// params.push(28);
+    /// let condition = Condition::raw_with_params("age BETWEEN $1 AND $2", params)
+    ///     .and(Condition::eq("name", "rick".to_string()));
+    ///
+    /// assert_eq!(condition.get_query(), "(age BETWEEN $1 AND $2) AND name = $3");
+    /// ```
+    pub fn raw_with_params(raw: &str, params: Bucket) -> Self {
+        Condition::new(Kind::Raw, raw.into(), params)
+    }
+
+    /// Create a condition from a query built from other expressions
+    pub(crate) fn simple(fragment: Fragment) -> Self {
+        Condition::from_fragment(Kind::Simple, fragment)
+    }
+
+    /// Create a condition from a field followed by a query numbered
+    /// against its own parameters
+    fn with_field<F: Into<Expression>>(field: F, query: String, params: Bucket) -> Self {
+        let mut fragment = field.into().into_field();
+        fragment.push(Fragment::new(query, params));
+        Condition::simple(fragment)
+    }
+
+    fn compare<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        operator: &str,
+        value: T,
+    ) -> Self {
+        let mut params = Bucket::new();
+        let index = params.push(value);
+        Condition::with_field(field, format!(" {} ${}", operator, index), params)
+    }
+
+    /// Create an equal condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::eq("id", 42).get_query(), "id = $1");
+    /// ```
+    pub fn eq<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(field: F, value: T) -> Self {
+        Condition::compare(field, "=", value)
+    }
+
+    /// Create a not equal condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::ne("id", 42).get_query(), "id <> $1");
+    /// ```
+    pub fn ne<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(field: F, value: T) -> Self {
+        Condition::compare(field, "<>", value)
+    }
+
+    /// Create a lower than condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::lt("age", 18).get_query(), "age < $1");
+    /// ```
+    pub fn lt<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(field: F, value: T) -> Self {
+        Condition::compare(field, "<", value)
+    }
+
+    /// Create a lower than or equal condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::lte("age", 18).get_query(), "age <= $1");
+    /// ```
+    pub fn lte<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(field: F, value: T) -> Self {
+        Condition::compare(field, "<=", value)
+    }
+
+    /// Create a greater than condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::gt("age", 18).get_query(), "age > $1");
+    /// ```
+    pub fn gt<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(field: F, value: T) -> Self {
+        Condition::compare(field, ">", value)
+    }
+
+    /// Create a greater than or equal condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::gte("age", 18).get_query(), "age >= $1");
+    /// ```
+    pub fn gte<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(field: F, value: T) -> Self {
+        Condition::compare(field, ">=", value)
+    }
+
+    /// Create a distinct condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::distinct("email", "rick@example.com".to_string()).get_query(), "email IS DISTINCT FROM $1");
+    /// ```
+    pub fn distinct<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        value: T,
+    ) -> Self {
+        Condition::compare(field, "IS DISTINCT FROM", value)
+    }
+
+    /// Create a not distinct condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::not_distinct("email", "rick@example.com".to_string()).get_query(), "email IS NOT DISTINCT FROM $1");
+    /// ```
+    pub fn not_distinct<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        value: T,
+    ) -> Self {
+        Condition::compare(field, "IS NOT DISTINCT FROM", value)
+    }
+
+    /// Create a like condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::like("name", "ri%".to_string()).get_query(), "name LIKE $1");
+    /// ```
+    pub fn like<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        value: T,
+    ) -> Self {
+        Condition::compare(field, "LIKE", value)
+    }
+
+    /// Create a case insensitive like condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::ilike("name", "ri%".to_string()).get_query(), "name ILIKE $1");
+    /// ```
+    pub fn ilike<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        value: T,
+    ) -> Self {
+        Condition::compare(field, "ILIKE", value)
+    }
+
+    /// Create a POSIX regular expression match condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::regex("name", "^ri".to_string()).get_query(), "name ~ $1");
+    /// ```
+    pub fn regex<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        value: T,
+    ) -> Self {
+        Condition::compare(field, "~", value)
+    }
+
+    /// Create a case insensitive POSIX regular expression match condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::iregex("name", "^ri".to_string()).get_query(), "name ~* $1");
+    /// ```
+    pub fn iregex<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        value: T,
+    ) -> Self {
+        Condition::compare(field, "~*", value)
+    }
+
+    /// Create a between condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::between("age", 18, 28).get_query(), "age BETWEEN $1 AND $2");
+    /// ```
+    pub fn between<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        low: T,
+        high: T,
+    ) -> Self {
+        let mut params = Bucket::new();
+        let low_index = params.push(low);
+        let high_index = params.push(high);
+        let query = format!(" BETWEEN ${} AND ${}", low_index, high_index);
+        Condition::with_field(field, query, params)
+    }
+
+    /// Create a null condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::is_null("deleted_at").get_query(), "deleted_at IS NULL");
+    /// ```
+    pub fn is_null<F: Into<Expression>>(field: F) -> Self {
+        Condition::with_field(field, " IS NULL".into(), Bucket::new())
+    }
+
+    /// Create a not null condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::is_not_null("deleted_at").get_query(), "deleted_at IS NOT NULL");
+    /// ```
+    pub fn is_not_null<F: Into<Expression>>(field: F) -> Self {
+        Condition::with_field(field, " IS NOT NULL".into(), Bucket::new())
+    }
+
+    fn list<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        operator: &str,
+        values: Vec<T>,
+        empty: &str,
+    ) -> Self {
+        if values.is_empty() {
+            return Condition::new(Kind::Simple, empty.into(), Bucket::new());
+        }
+        let mut params = Bucket::new();
+        let indexes: Vec<String> = values
+            .into_iter()
+            .map(|value| format!("${}", params.push(value)))
+            .collect();
+        let query = format!(" {} ({})", operator, indexes.join(", "));
+        Condition::with_field(field, query, params)
+    }
+
+    fn array<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        operator: &str,
+        values: Vec<T>,
+        empty: &str,
+    ) -> Self {
+        if values.is_empty() {
+            return Condition::new(Kind::Simple, empty.into(), Bucket::new());
+        }
+        let mut params = Bucket::new();
+        let index = params.push(values);
+        let query = format!(" {}(${})", operator, index);
+        Condition::with_field(field, query, params)
+    }
+
+    /// Create an in condition, binding each value as a parameter
+    ///
+    /// An empty list results in an always false condition. See `eq_any` to
+    /// bind the values as a single array parameter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::is_in("id", vec![1, 2]).get_query(), "id IN ($1, $2)");
+    /// assert_eq!(Condition::is_in::<_, i32>("id", vec![]).get_query(), "false");
+    /// ```
+    pub fn is_in<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        values: Vec<T>,
+    ) -> Self {
+        Condition::list(field, "IN", values, "false")
+    }
+
+    /// Create a not in condition, binding each value as a parameter
+    ///
+    /// An empty list results in an always true condition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::is_not_in("id", vec![1, 2]).get_query(), "id NOT IN ($1, $2)");
+    /// assert_eq!(Condition::is_not_in::<_, i32>("id", vec![]).get_query(), "true");
+    /// ```
+    pub fn is_not_in<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        values: Vec<T>,
+    ) -> Self {
+        Condition::list(field, "NOT IN", values, "true")
+    }
+
+    /// Create an equal any condition, binding the values as a single array parameter
+    ///
+    /// An empty list results in an always false condition. Unlike `is_in`, the
+    /// query doesn't depend on the number of values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::eq_any("id", vec![1, 2]).get_query(), "id = ANY($1)");
+    /// ```
+    pub fn eq_any<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        values: Vec<T>,
+    ) -> Self {
+        Condition::array(field, "= ANY", values, "false")
+    }
+
+    /// Create a not equal all condition, binding the values as a single array parameter
+    ///
+    /// An empty list results in an always true condition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// assert_eq!(Condition::ne_all("id", vec![1, 2]).get_query(), "id <> ALL($1)");
+    /// ```
+    pub fn ne_all<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        field: F,
+        values: Vec<T>,
+    ) -> Self {
+        Condition::array(field, "<> ALL", values, "true")
+    }
+
+    fn subquery<F: Into<Expression>, Q: SelectQuery>(prefix: F, query: Q) -> Self {
+        let subquery = format!(" ({})", query.get_query());
+        Condition::with_field(prefix, subquery, query.get_params())
+    }
+
+    /// Create an in subquery condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Condition, SelectBuilder};
+    ///
+    /// let mut authors = SelectBuilder::new("articles");
+    /// authors.select("author_id");
+    ///
+    /// assert_eq!(
+    ///     Condition::in_select("id", authors).get_query(),
+    ///     "id IN (SELECT author_id FROM articles)"
+    /// );
+    /// ```
+    pub fn in_select<F: Into<Expression>, Q: SelectQuery>(field: F, query: Q) -> Self {
+        let mut prefix = field.into().into_field();
+        prefix.push_str(" IN");
+        Condition::subquery(Expression::atom(prefix), query)
+    }
+
+    /// Create a not in subquery condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Condition, SelectBuilder};
+    ///
+    /// let mut authors = SelectBuilder::new("articles");
+    /// authors.select("author_id");
+    ///
+    /// assert_eq!(
+    ///     Condition::not_in_select("id", authors).get_query(),
+    ///     "id NOT IN (SELECT author_id FROM articles)"
+    /// );
+    /// ```
+    pub fn not_in_select<F: Into<Expression>, Q: SelectQuery>(field: F, query: Q) -> Self {
+        let mut prefix = field.into().into_field();
+        prefix.push_str(" NOT IN");
+        Condition::subquery(Expression::atom(prefix), query)
+    }
+
+    /// Create an exists condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Condition, SelectBuilder};
+    ///
+    /// assert_eq!(
+    ///     Condition::exists(SelectBuilder::new("articles")).get_query(),
+    ///     "EXISTS (SELECT * FROM articles)"
+    /// );
+    /// ```
+    pub fn exists<Q: SelectQuery>(query: Q) -> Self {
+        Condition::subquery("EXISTS", query)
+    }
+
+    /// Create a not exists condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Condition, SelectBuilder};
+    ///
+    /// assert_eq!(
+    ///     Condition::not_exists(SelectBuilder::new("articles")).get_query(),
+    ///     "NOT EXISTS (SELECT * FROM articles)"
+    /// );
+    /// ```
+    pub fn not_exists<Q: SelectQuery>(query: Q) -> Self {
+        Condition::subquery("NOT EXISTS", query)
+    }
+
+    /// Wrap the condition in parentheses
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// let condition = Condition::eq("id", 42).group();
+    ///
+    /// assert_eq!(condition.get_query(), "(id = $1)");
+    /// ```
+    pub fn group(self) -> Self {
+        Condition::from_fragment(Kind::Simple, self.fragment.wrap("(", ")"))
+    }
+
+    /// Combine two conditions with `AND`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// let condition = Condition::eq("id", 42).and(Condition::ne("name", "rick".to_string()));
+    ///
+    /// assert_eq!(condition.get_query(), "id = $1 AND name <> $2");
+    /// ```
+    pub fn and(self, other: Condition) -> Self {
+        self.combine(other, Kind::And)
+    }
+
+    /// Combine two conditions with `OR`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// let condition = Condition::eq("id", 42).or(Condition::eq("id", 43));
+    ///
+    /// assert_eq!(condition.get_query(), "id = $1 OR id = $2");
+    /// ```
+    pub fn or(self, other: Condition) -> Self {
+        self.combine(other, Kind::Or)
+    }
+
+    /// Combine a list of conditions with `AND`
+    ///
+    /// An empty list results in an always true condition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// let condition = Condition::all(vec![
+    ///     Condition::eq("id", 42),
+    ///     Condition::eq("id", 43).or(Condition::eq("id", 44)),
+    /// ]);
+    ///
+    /// assert_eq!(condition.get_query(), "id = $1 AND (id = $2 OR id = $3)");
+    /// ```
+    pub fn all(conditions: Vec<Condition>) -> Self {
+        Condition::fold(conditions, Kind::And, "true")
+    }
+
+    /// Combine a list of conditions with `OR`
+    ///
+    /// An empty list results in an always false condition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    ///
+    /// let condition = Condition::any(vec![Condition::eq("id", 42), Condition::eq("id", 43)]);
+    ///
+    /// assert_eq!(condition.get_query(), "id = $1 OR id = $2");
+    /// ```
+    pub fn any(conditions: Vec<Condition>) -> Self {
+        Condition::fold(conditions, Kind::Or, "false")
+    }
+
+    /// Get the query of the condition
+    pub fn get_query(&self) -> String {
+        self.fragment.to_string()
+    }
+
+    /// Get the parameters of the condition
+    pub fn get_params(self) -> Bucket {
+        self.fragment.into_params()
+    }
+
+    pub(crate) fn into_fragment(self) -> Fragment {
+        self.fragment
+    }
+
+    /// Get the condition as a fragment safe to join with other conditions
+    /// using `AND`
+    pub(crate) fn into_operand(self) -> Fragment {
+        self.into_operand_for(&Kind::And)
+    }
+
+    /// Move the parameters of the condition into a bucket, returning the
+    /// query safe to join with other conditions using `AND`
+    pub(crate) fn merge_into(self, params: &mut Bucket) -> String {
+        self.into_operand().merge_into(params)
+    }
+
+    fn into_operand_for(self, kind: &Kind) -> Fragment {
+        match (&self.kind, kind) {
+            (Kind::Raw, _) | (Kind::Or, Kind::And) => self.fragment.wrap("(", ")"),
+            _ => self.fragment,
+        }
+    }
+
+    fn combine(self, other: Condition, kind: Kind) -> Self {
+        let operator = if kind == Kind::And { " AND " } else { " OR " };
+        let mut fragment = self.into_operand_for(&kind);
+        fragment.push_str(operator);
+        fragment.push(other.into_operand_for(&kind));
+        Condition::from_fragment(kind, fragment)
+    }
+
+    fn fold(conditions: Vec<Condition>, kind: Kind, empty: &str) -> Self {
+        let mut iter = conditions.into_iter();
+        match iter.next() {
+            Some(first) => iter.fold(first, |result, item| result.combine(item, kind.clone())),
+            None => Condition::new(Kind::Simple, empty.into(), Bucket::new()),
+        }
+    }
+}
+
+impl Not for Condition {
+    type Output = Condition;
+
+    fn not(self) -> Self::Output {
+        Condition::from_fragment(Kind::Simple, self.fragment.wrap("NOT (", ")"))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn nested_groups() {
+        let condition = Condition::eq("a", 1)
+            .and(Condition::eq("b", 2).or(Condition::eq("c", 3)))
+            .or(!Condition::eq("d", 4).and(Condition::raw("e OR f")));
+        assert_eq!(
+            condition.get_query(),
+            "a = $1 AND (b = $2 OR c = $3) OR NOT (d = $4 AND (e OR f))"
+        );
+        assert_eq!(condition.get_params().len(), 4);
+    }
+
+    #[test]
+    fn explicit_group() {
+        let condition = Condition::eq("a", 1)
+            .or(Condition::eq("b", 2))
+            .group()
+            .or(Condition::eq("c", 3));
+        assert_eq!(condition.get_query(), "(a = $1 OR b = $2) OR c = $3");
+    }
+
+    #[test]
+    fn comparisons() {
+        let condition = Condition::between("age", 18, 28)
+            .and(Condition::ilike("name", "ri%"))
+            .or(Condition::is_null("age"));
+        assert_eq!(
+            condition.get_query(),
+            "age BETWEEN $1 AND $2 AND name ILIKE $3 OR age IS NULL"
+        );
+        assert_eq!(condition.get_params().len(), 3);
+    }
+
+    #[test]
+    fn subqueries() {
+        use crate::prelude::QueryBuilderWithWhere;
+        use crate::select_builder::SelectBuilder;
+
+        let mut subquery = SelectBuilder::new("articles");
+        subquery.select("author_id");
+        subquery.where_eq("published", true);
+        let condition = Condition::eq("active", true).or(Condition::in_select("id", subquery));
+        assert_eq!(
+            condition.get_query(),
+            "active = $1 OR id IN (SELECT author_id FROM articles WHERE published = $2)"
+        );
+        assert_eq!(condition.get_params().len(), 2);
+    }
+
+    #[test]
+    fn empty_lists() {
+        assert_eq!(Condition::all(vec![]).get_query(), "true");
+        assert_eq!(Condition::any(vec![]).get_query(), "false");
+    }
+
+    #[test]
+    fn merge_into_groups_or() {
+        let mut params = Bucket::new();
+        let query = Condition::eq("a", 1)
+            .or(Condition::eq("b", 2))
+            .merge_into(&mut params);
+        assert_eq!(query, "(a = $1 OR b = $2)");
+        assert_eq!(params.len(), 2);
+    }
+
+    #[test]
+    fn raw_conditions_mixed_with_params() {
+        let mut params = Bucket::new();
+        params.push(1);
+        let condition = Condition::raw("a = $1").and(Condition::eq("b", 2));
+        assert_eq!(condition.merge_into(&mut params), "(a = $1) AND b = $2");
+        let condition = !Condition::eq("c", 3).or(Condition::raw("d = $1"));
+        assert_eq!(
+            condition.merge_into(&mut params),
+            "NOT (c = $3 OR (d = $1))"
+        );
+        assert_eq!(params.len(), 3);
+    }
+}
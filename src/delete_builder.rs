@@ -0,0 +1,220 @@
+use crate::bucket::Bucket;
+use crate::expression::Expression;
+use crate::prelude::*;
+use crate::with_query::{with_clause, WithQuery};
+use postgres_types::ToSql;
+
+pub struct DeleteBuilder {
+    with_queries: Vec<WithQuery>,
+    table: String,
+    using: Vec<String>,
+    conditions: Vec<String>,
+    returning: Vec<String>,
+    params: Bucket,
+}
+
+impl DeleteBuilder {
+    /// Create a new delete builder for a given table
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::DeleteBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithWhere};
+    ///
+    /// let mut builder = DeleteBuilder::new("users");
+    /// builder.where_eq("id", 42);
+    ///
+    /// assert_eq!(builder.get_query(), "DELETE FROM users WHERE id = $1");
+    /// ```
+    pub fn new(from: &str) -> Self {
+        DeleteBuilder {
+            with_queries: vec![],
+            table: from.into(),
+            using: vec![],
+            conditions: vec![],
+            returning: vec![],
+            params: Bucket::new(),
+        }
+    }
+
+    /// Add a table to the using clause
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::DeleteBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithWhere};
+    ///
+    /// let mut builder = DeleteBuilder::new("users");
+    /// builder.using("banned_emails");
+    /// builder.where_condition("users.email = banned_emails.email");
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "DELETE FROM users USING banned_emails WHERE users.email = banned_emails.email"
+    /// );
+    /// ```
+    pub fn using(&mut self, table: &str) -> &mut Self {
+        self.using.push(table.into());
+        self
+    }
+}
+
+impl DeleteBuilder {
+    fn with_queries_to_query(&self) -> Option<String> {
+        with_clause(&self.with_queries)
+    }
+
+    fn table_to_query(&self) -> String {
+        format!("DELETE FROM {}", self.table)
+    }
+
+    fn using_to_query(&self) -> Option<String> {
+        if !self.using.is_empty() {
+            Some(format!("USING {}", self.using.join(", ")))
+        } else {
+            None
+        }
+    }
+
+    fn where_to_query(&self) -> Option<String> {
+        if !self.conditions.is_empty() {
+            let where_query = self.conditions.join(" AND ");
+            Some(format!("WHERE {}", where_query))
+        } else {
+            None
+        }
+    }
+
+    fn returning_to_query(&self) -> Option<String> {
+        if !self.returning.is_empty() {
+            Some(format!("RETURNING {}", self.returning.join(", ")))
+        } else {
+            None
+        }
+    }
+}
+
+impl QueryBuilder for DeleteBuilder {
+    fn add_param<T: 'static + ToSql + Sync + Clone>(&mut self, value: T) -> usize {
+        self.params.push(value)
+    }
+
+    fn merge_params(&mut self, query: &str, params: Bucket) -> String {
+        self.params.merge(query, params)
+    }
+
+    fn get_query(&self) -> String {
+        let mut result: Vec<String> = vec![];
+        if let Some(value) = self.with_queries_to_query() {
+            result.push(value);
+        }
+        result.push(self.table_to_query());
+        if let Some(value) = self.using_to_query() {
+            result.push(value);
+        }
+        if let Some(value) = self.where_to_query() {
+            result.push(value);
+        }
+        if let Some(value) = self.returning_to_query() {
+            result.push(value);
+        }
+        result.join(" ")
+    }
+
+    fn get_params(self) -> Bucket {
+        self.params
+    }
+
+    fn get_ref_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params.get_refs()
+    }
+}
+
+impl QueryBuilderWithWhere for DeleteBuilder {
+    fn where_condition(&mut self, raw: &str) -> &mut Self {
+        self.conditions.push(raw.to_string());
+        self
+    }
+}
+
+impl QueryBuilderWithReturning for DeleteBuilder {
+    /// Add a column to return from the deleted rows
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::DeleteBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithReturning, QueryBuilderWithWhere};
+    ///
+    /// let mut builder = DeleteBuilder::new("users");
+    /// builder.where_eq("id", 42);
+    /// builder.returning("id");
+    /// builder.returning("name");
+    ///
+    /// assert_eq!(builder.get_query(), "DELETE FROM users WHERE id = $1 RETURNING id, name");
+    /// ```
+    fn returning<E: Into<Expression>>(&mut self, column: E) -> &mut Self {
+        let column = column.into().merge_into(&mut self.params);
+        self.returning.push(column);
+        self
+    }
+}
+
+impl ModifyingQuery for DeleteBuilder {}
+
+impl QueryBuilderWithQueries for DeleteBuilder {
+    fn with(&mut self, query: WithQuery) -> &mut Self {
+        let query = query.merge_into(&mut self.params);
+        self.with_queries.push(query);
+        self
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn from_scratch() {
+        let builder = DeleteBuilder::new("publishers");
+        assert_eq!(builder.get_query(), "DELETE FROM publishers");
+    }
+
+    #[test]
+    fn with_where() {
+        let mut builder = DeleteBuilder::new("publishers");
+        builder.where_eq("trololo", 42);
+        builder.where_ne("tralala", true);
+        assert_eq!(
+            builder.get_query(),
+            "DELETE FROM publishers WHERE trololo = $1 AND tralala <> $2"
+        );
+    }
+
+    #[test]
+    fn with_using_and_returning() {
+        let mut builder = DeleteBuilder::new("publishers");
+        builder.using("articles");
+        builder.using("authors");
+        builder.where_condition("articles.publisher_id = publishers.id");
+        builder.where_eq("authors.id", 42);
+        builder.returning("publishers.*");
+        assert_eq!(
+            builder.get_query(),
+            "DELETE FROM publishers USING articles, authors WHERE articles.publisher_id = publishers.id AND authors.id = $1 RETURNING publishers.*"
+        );
+    }
+
+    #[test]
+    fn with_subquery() {
+        let mut builder = DeleteBuilder::new("publishers");
+        builder.with_query("inactive", "SELECT id FROM publishers WHERE active = false");
+        builder.where_condition("id IN (SELECT id FROM inactive)");
+        assert_eq!(
+            builder.get_query(),
+            "WITH inactive AS (SELECT id FROM publishers WHERE active = false) DELETE FROM publishers WHERE id IN (SELECT id FROM inactive)"
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The `DISTINCT ON` expressions don't match the leftmost `ORDER BY` expressions
+    DistinctOnOrderMismatch,
+    /// A locking clause is used with `DISTINCT`, `GROUP BY` or `HAVING`
+    LockingNotAllowed,
+    /// An `ON CONFLICT DO UPDATE` clause has no conflict target, or the
+    /// conflict update is set without any `ON CONFLICT` clause
+    ConflictTargetRequired,
+    /// An inner, left, right or full join has no `ON`, `USING` or `NATURAL` constraint
+    JoinConstraintRequired,
+    /// A cross join has an `ON`, `USING` or `NATURAL` constraint
+    JoinConstraintNotAllowed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DistinctOnOrderMismatch => write!(
+                f,
+                "SELECT DISTINCT ON expressions must match initial ORDER BY expressions"
+            ),
+            Error::LockingNotAllowed => write!(
+                f,
+                "row locking is not allowed with DISTINCT, GROUP BY or HAVING clauses"
+            ),
+            Error::ConflictTargetRequired => write!(
+                f,
+                "ON CONFLICT DO UPDATE requires inference specification or constraint name"
+            ),
+            Error::JoinConstraintRequired => write!(
+                f,
+                "INNER, LEFT, RIGHT and FULL joins require an ON, USING or NATURAL constraint"
+            ),
+            Error::JoinConstraintNotAllowed => write!(
+                f,
+                "CROSS joins don't accept an ON, USING or NATURAL constraint"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,602 @@
+use crate::bucket::Bucket;
+use crate::condition::Condition;
+use crate::fragment::Fragment;
+use crate::prelude::SelectQuery;
+use postgres_types::ToSql;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Raw,
+    Atom,
+    Operation,
+}
+
+/// A constant value written in the query
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::Literal;
+///
+/// assert_eq!(Literal::from("it's").to_string(), "'it''s'");
+/// assert_eq!(Literal::from(42).to_string(), "42");
+/// assert_eq!(Literal::Null.to_string(), "NULL");
+/// ```
+pub enum Literal {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Literal::Null => write!(f, "NULL"),
+            Literal::Bool(value) => write!(f, "{}", if *value { "TRUE" } else { "FALSE" }),
+            Literal::Integer(value) => write!(f, "{}", value),
+            Literal::Float(value) if value.is_nan() => write!(f, "'NaN'::float8"),
+            Literal::Float(value) if value.is_infinite() => {
+                let sign = if value.is_sign_negative() { "-" } else { "" };
+                write!(f, "'{}Infinity'::float8", sign)
+            }
+            Literal::Float(value) => write!(f, "{:?}", value),
+            Literal::Text(value) => write!(f, "'{}'", value.replace('\'', "''")),
+        }
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(value: bool) -> Self {
+        Literal::Bool(value)
+    }
+}
+
+impl From<i32> for Literal {
+    fn from(value: i32) -> Self {
+        Literal::Integer(value.into())
+    }
+}
+
+impl From<i64> for Literal {
+    fn from(value: i64) -> Self {
+        Literal::Integer(value)
+    }
+}
+
+impl From<f64> for Literal {
+    fn from(value: f64) -> Self {
+        Literal::Float(value)
+    }
+}
+
+impl From<&str> for Literal {
+    fn from(value: &str) -> Self {
+        Literal::Text(value.into())
+    }
+}
+
+impl From<String> for Literal {
+    fn from(value: String) -> Self {
+        Literal::Text(value)
+    }
+}
+
+/// A value expression carrying its own parameters
+///
+/// The placeholders of an expression are numbered from `$1` against its own
+/// parameters and are shifted when the expression is added to a builder.
+/// Raw strings can be used wherever an expression is expected, their
+/// placeholders then refer to the parameters of the builder.
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::{Expression, SelectBuilder};
+/// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithWhere};
+///
+/// let price = Expression::column("price") * Expression::param(1.2f64);
+/// let mut builder = SelectBuilder::new("products");
+/// builder.where_eq("available", true);
+/// builder.select("name");
+/// builder.select(Expression::function("round", vec![price, Expression::literal(2)]));
+///
+/// assert_eq!(
+///     builder.get_query(),
+///     "SELECT name, round(price * $2, 2) FROM products WHERE available = $1"
+/// );
+/// ```
+pub struct Expression {
+    kind: Kind,
+    fragment: Fragment,
+}
+
+impl Expression {
+    fn new(kind: Kind, query: String, params: Bucket) -> Self {
+        Expression::from_fragment(kind, Fragment::new(query, params))
+    }
+
+    fn from_fragment(kind: Kind, fragment: Fragment) -> Self {
+        Expression { kind, fragment }
+    }
+
+    /// Create an expression that can be used as an operand without parentheses
+    pub(crate) fn atom(fragment: Fragment) -> Self {
+        Expression::from_fragment(Kind::Atom, fragment)
+    }
+
+    /// Create an expression from a raw string without parameters
+    pub fn raw(raw: &str) -> Self {
+        Expression::new(Kind::Raw, raw.into(), Bucket::new())
+    }
+
+    /// Create an expression from a raw string and the parameters it refers to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Expression;
+    /// use postgres_querybuilder::bucket::Bucket;
+    ///
+    /// let mut params = Bucket::new();
+    /// params.push(3);
+    /// let expression = Expression::param(2) * Expression::raw_with_params("$1 + 1", params);
+    ///
+    /// assert_eq!(expression.get_query(), "$1 * ($2 + 1)");
+    /// ```
+    pub fn raw_with_params(raw: &str, params: Bucket) -> Self {
+        Expression::new(Kind::Raw, raw.into(), params)
+    }
+
+    /// Create a reference to a column
+    pub fn column(name: &str) -> Self {
+        Expression::new(Kind::Atom, name.into(), Bucket::new())
+    }
+
+    /// Create a parameter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Expression;
+    ///
+    /// assert_eq!(Expression::param(42).get_query(), "$1");
+    /// ```
+    pub fn param<T: 'static + ToSql + Sync + Clone>(value: T) -> Self {
+        let mut params = Bucket::new();
+        let index = params.push(value);
+        Expression::new(Kind::Atom, format!("${}", index), params)
+    }
+
+    /// Create a constant written in the query
+    pub fn literal<L: Into<Literal>>(value: L) -> Self {
+        Expression::new(Kind::Atom, value.into().to_string(), Bucket::new())
+    }
+
+    /// Create a function call, merging the parameters of its arguments
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Expression;
+    ///
+    /// let expression = Expression::function(
+    ///     "coalesce",
+    ///     vec![Expression::column("nickname"), Expression::param("anonymous".to_string())],
+    /// );
+    ///
+    /// assert_eq!(expression.get_query(), "coalesce(nickname, $1)");
+    /// ```
+    pub fn function(name: &str, arguments: Vec<Expression>) -> Self {
+        let arguments = arguments
+            .into_iter()
+            .map(|argument| argument.fragment)
+            .collect();
+        let call = Fragment::join(arguments, ", ").wrap(format!("{}(", name).as_str(), ")");
+        Expression::from_fragment(Kind::Atom, call)
+    }
+
+    /// Create a scalar subquery, merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Expression, SelectBuilder};
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithWhere};
+    ///
+    /// let mut count = SelectBuilder::new("articles");
+    /// count.select("count(*)");
+    /// count.where_condition("articles.author_id = users.id");
+    /// count.where_eq("articles.published", true);
+    /// let expression = Expression::select(count);
+    ///
+    /// assert_eq!(
+    ///     expression.get_query(),
+    ///     "(SELECT count(*) FROM articles WHERE articles.author_id = users.id AND articles.published = $1)"
+    /// );
+    /// ```
+    pub fn select<Q: SelectQuery>(query: Q) -> Self {
+        let subquery = format!("({})", query.get_query());
+        Expression::new(Kind::Atom, subquery, query.get_params())
+    }
+
+    fn into_operand(self) -> Fragment {
+        match self.kind {
+            Kind::Atom => self.fragment,
+            _ => self.fragment.wrap("(", ")"),
+        }
+    }
+
+    /// Combine two expressions with a binary operator, merging their parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Expression;
+    ///
+    /// let expression = Expression::column("tags")
+    ///     .operate("||", Expression::param(vec!["new".to_string()]))
+    ///     .operate("@>", Expression::raw("ARRAY['a']"));
+    ///
+    /// assert_eq!(expression.get_query(), "(tags || $1) @> (ARRAY['a'])");
+    /// ```
+    pub fn operate(self, operator: &str, other: Expression) -> Self {
+        let mut fragment = self.into_operand();
+        fragment.push_str(format!(" {} ", operator).as_str());
+        fragment.push(other.into_operand());
+        Expression::from_fragment(Kind::Operation, fragment)
+    }
+
+    /// Concatenate two expressions
+    pub fn concat(self, other: Expression) -> Self {
+        self.operate("||", other)
+    }
+
+    /// Convert the expression to a type
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Expression;
+    ///
+    /// let expression = Expression::param("42".to_string()).cast("integer");
+    ///
+    /// assert_eq!(expression.get_query(), "CAST($1 AS integer)");
+    /// ```
+    pub fn cast(self, data_type: &str) -> Self {
+        let suffix = format!(" AS {})", data_type);
+        let fragment = self.fragment.wrap("CAST(", suffix.as_str());
+        Expression::from_fragment(Kind::Atom, fragment)
+    }
+
+    fn compare(self, operator: &str, other: Expression) -> Condition {
+        Condition::simple(self.operate(operator, other).fragment)
+    }
+
+    /// Create an equal condition between two expressions
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Expression, SelectBuilder};
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithWhere};
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_eq("active", true);
+    /// builder.where_cond(
+    ///     Expression::function("lower", vec![Expression::column("email")])
+    ///         .eq(Expression::param("rick@example.com".to_string())),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users WHERE active = $1 AND lower(email) = $2"
+    /// );
+    /// ```
+    pub fn eq(self, other: Expression) -> Condition {
+        self.compare("=", other)
+    }
+
+    /// Create a not equal condition between two expressions
+    pub fn ne(self, other: Expression) -> Condition {
+        self.compare("<>", other)
+    }
+
+    /// Create a lower than condition between two expressions
+    pub fn lt(self, other: Expression) -> Condition {
+        self.compare("<", other)
+    }
+
+    /// Create a lower or equal condition between two expressions
+    pub fn lte(self, other: Expression) -> Condition {
+        self.compare("<=", other)
+    }
+
+    /// Create a greater than condition between two expressions
+    pub fn gt(self, other: Expression) -> Condition {
+        self.compare(">", other)
+    }
+
+    /// Create a greater or equal condition between two expressions
+    pub fn gte(self, other: Expression) -> Condition {
+        self.compare(">=", other)
+    }
+
+    /// Create a condition matching a `LIKE` pattern
+    pub fn like(self, pattern: Expression) -> Condition {
+        self.compare("LIKE", pattern)
+    }
+
+    /// Create a condition checking that the expression is null
+    pub fn is_null(self) -> Condition {
+        let mut fragment = self.into_operand();
+        fragment.push_str(" IS NULL");
+        Condition::simple(fragment)
+    }
+
+    /// Create a condition checking that the expression is not null
+    pub fn is_not_null(self) -> Condition {
+        let mut fragment = self.into_operand();
+        fragment.push_str(" IS NOT NULL");
+        Condition::simple(fragment)
+    }
+
+    pub fn get_query(&self) -> String {
+        self.fragment.to_string()
+    }
+
+    pub fn get_params(self) -> Bucket {
+        self.fragment.into_params()
+    }
+
+    pub(crate) fn into_fragment(self) -> Fragment {
+        self.fragment
+    }
+
+    /// Get the expression as the left operand of a condition, keeping raw
+    /// strings as is
+    pub(crate) fn into_field(self) -> Fragment {
+        match self.kind {
+            Kind::Operation => self.fragment.wrap("(", ")"),
+            _ => self.fragment,
+        }
+    }
+
+    /// Move the parameters of the expression into a bucket, returning the
+    /// query with its placeholders shifted accordingly
+    ///
+    /// Raw strings are kept as is, even inside a function call or an
+    /// operation, since their placeholders refer to the parameters of the
+    /// bucket.
+    pub(crate) fn merge_into(self, params: &mut Bucket) -> String {
+        self.fragment.merge_into(params)
+    }
+}
+
+impl From<&str> for Expression {
+    fn from(raw: &str) -> Self {
+        Expression::raw(raw)
+    }
+}
+
+impl From<String> for Expression {
+    fn from(raw: String) -> Self {
+        Expression::new(Kind::Raw, raw, Bucket::new())
+    }
+}
+
+impl From<Literal> for Expression {
+    fn from(value: Literal) -> Self {
+        Expression::literal(value)
+    }
+}
+
+impl From<Condition> for Expression {
+    fn from(condition: Condition) -> Self {
+        Expression::from_fragment(Kind::Operation, condition.into_fragment())
+    }
+}
+
+impl Add for Expression {
+    type Output = Expression;
+
+    fn add(self, other: Expression) -> Expression {
+        self.operate("+", other)
+    }
+}
+
+impl Sub for Expression {
+    type Output = Expression;
+
+    fn sub(self, other: Expression) -> Expression {
+        self.operate("-", other)
+    }
+}
+
+impl Mul for Expression {
+    type Output = Expression;
+
+    fn mul(self, other: Expression) -> Expression {
+        self.operate("*", other)
+    }
+}
+
+impl Div for Expression {
+    type Output = Expression;
+
+    fn div(self, other: Expression) -> Expression {
+        self.operate("/", other)
+    }
+}
+
+/// Quote an identifier unless it is a plain lowercase identifier
+///
+/// Keywords are kept as is, since they are accepted as column labels after `AS`.
+pub(crate) fn quote_identifier(name: &str) -> String {
+    let plain = name
+        .chars()
+        .next()
+        .map(|first| first.is_ascii_lowercase() || first == '_')
+        .unwrap_or(false)
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// A `CASE` expression carrying its own parameters
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::{Case, Condition, Expression};
+///
+/// let expression: Expression = Case::new(Condition::lt("age", 18), Expression::literal("minor"))
+///     .when(Condition::gte("age", 65), Expression::param("senior".to_string()))
+///     .otherwise(Expression::literal("adult"))
+///     .into();
+///
+/// assert_eq!(
+///     expression.get_query(),
+///     "CASE WHEN age < $1 THEN 'minor' WHEN age >= $2 THEN $3 ELSE 'adult' END"
+/// );
+/// ```
+pub struct Case {
+    branches: Vec<Fragment>,
+    otherwise: Option<Fragment>,
+}
+
+impl Case {
+    /// Create a `CASE` expression from its first branch
+    pub fn new<E: Into<Expression>>(condition: Condition, result: E) -> Self {
+        Case {
+            branches: vec![Case::branch(condition, result)],
+            otherwise: None,
+        }
+    }
+
+    fn branch<E: Into<Expression>>(condition: Condition, result: E) -> Fragment {
+        let mut branch = condition.into_fragment().wrap("WHEN ", " THEN ");
+        branch.push(result.into().fragment);
+        branch
+    }
+
+    /// Add a branch returning a result when a condition is true
+    pub fn when<E: Into<Expression>>(mut self, condition: Condition, result: E) -> Self {
+        self.branches.push(Case::branch(condition, result));
+        self
+    }
+
+    /// Set the result when no condition is true
+    pub fn otherwise<E: Into<Expression>>(mut self, result: E) -> Self {
+        self.otherwise = Some(result.into().fragment.wrap("ELSE ", ""));
+        self
+    }
+}
+
+impl From<Case> for Expression {
+    fn from(case: Case) -> Self {
+        let mut sections = vec![Fragment::raw("CASE")];
+        sections.extend(case.branches);
+        sections.extend(case.otherwise);
+        sections.push(Fragment::raw("END"));
+        Expression::from_fragment(Kind::Atom, Fragment::join(sections, " "))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn operators_precedence() {
+        let expression = (Expression::column("a") + Expression::param(1))
+            * (Expression::column("b") - Expression::param(2))
+            / Expression::raw("c + d");
+        assert_eq!(expression.get_query(), "((a + $1) * (b - $2)) / (c + d)");
+        assert_eq!(expression.get_params().len(), 2);
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(Expression::literal(true).get_query(), "TRUE");
+        assert_eq!(Expression::literal(1.5).get_query(), "1.5");
+        assert_eq!(Expression::literal(2.0).get_query(), "2.0");
+        assert_eq!(Expression::literal(f64::NAN).get_query(), "'NaN'::float8");
+        assert_eq!(
+            Expression::literal(f64::NEG_INFINITY).get_query(),
+            "'-Infinity'::float8"
+        );
+        assert_eq!(Expression::literal("a'b").get_query(), "'a''b'");
+    }
+
+    #[test]
+    fn conditions_with_expressions() {
+        let condition = Expression::column("a")
+            .concat(Expression::param("x".to_string()))
+            .eq(Expression::param("yx".to_string()))
+            .and(Expression::select(crate::SelectBuilder::new("b")).is_not_null());
+        assert_eq!(
+            condition.get_query(),
+            "(a || $1) = $2 AND (SELECT * FROM b) IS NOT NULL"
+        );
+    }
+
+    #[test]
+    fn case_with_conditions() {
+        let expression: Expression = Case::new(
+            Condition::eq("a", 1).or(Condition::eq("b", 2)),
+            Expression::param(3),
+        )
+        .into();
+        let expression = expression + Expression::param(4);
+        assert_eq!(
+            expression.get_query(),
+            "CASE WHEN a = $1 OR b = $2 THEN $3 END + $4"
+        );
+    }
+
+    #[test]
+    fn quote_identifiers() {
+        assert_eq!(quote_identifier("total_2"), "total_2");
+        assert_eq!(quote_identifier("select"), "select");
+        assert_eq!(quote_identifier("Total"), "\"Total\"");
+        assert_eq!(quote_identifier("2nd"), "\"2nd\"");
+        assert_eq!(quote_identifier("full name"), "\"full name\"");
+        assert_eq!(quote_identifier("a\"b"), "\"a\"\"b\"");
+        assert_eq!(quote_identifier(""), "\"\"");
+    }
+
+    #[test]
+    fn raw_strings_keep_placeholders() {
+        let mut params = Bucket::new();
+        params.push(1);
+        assert_eq!(Expression::from("$1 + 1").merge_into(&mut params), "$1 + 1");
+        assert_eq!(Expression::param(2).merge_into(&mut params), "$2");
+    }
+
+    #[test]
+    fn raw_strings_mixed_with_params() {
+        let mut params = Bucket::new();
+        params.push(1);
+        let expression =
+            Expression::function("f", vec![Expression::raw("$1"), Expression::param(2)])
+                * Expression::param(3);
+        assert_eq!(expression.merge_into(&mut params), "f($1, $2) * $3");
+        let expression: Expression = Case::new(Condition::raw("a = $1"), Expression::param(4))
+            .otherwise(Expression::raw("$1").cast("text"))
+            .into();
+        assert_eq!(
+            expression.merge_into(&mut params),
+            "CASE WHEN a = $1 THEN $4 ELSE CAST($1 AS text) END"
+        );
+        assert_eq!(params.len(), 4);
+    }
+}
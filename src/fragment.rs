@@ -0,0 +1,149 @@
+use crate::bucket::{shift_placeholders, Bucket};
+use postgres_types::ToSql;
+use std::fmt;
+
+/// A piece of query made of parts numbering their placeholders independently
+///
+/// A part with parameters numbers its placeholders from `$1` against them,
+/// while a part without parameters, such as a raw string, keeps placeholders
+/// referring to the parameters of the builder it is added to. Keeping the
+/// parts apart allows both to be combined without renumbering raw strings.
+pub(crate) struct Fragment {
+    parts: Vec<(String, Bucket)>,
+}
+
+impl Fragment {
+    pub(crate) fn new(query: String, params: Bucket) -> Self {
+        Fragment {
+            parts: vec![(query, params)],
+        }
+    }
+
+    /// Create a fragment from a raw string without parameters
+    pub(crate) fn raw(query: &str) -> Self {
+        Fragment::new(query.into(), Bucket::new())
+    }
+
+    /// Append a raw string without parameters
+    pub(crate) fn push_str(&mut self, query: &str) {
+        self.push_part(query.into(), Bucket::new());
+    }
+
+    /// Append another fragment, keeping the numbering of its parts
+    pub(crate) fn push(&mut self, other: Fragment) {
+        for (query, params) in other.parts {
+            self.push_part(query, params);
+        }
+    }
+
+    fn push_part(&mut self, query: String, params: Bucket) {
+        if let Some((last, bucket)) = self.parts.last_mut() {
+            // parts with parameters can share their numbering, and so can
+            // the parts without, but never one of each
+            if bucket.is_empty() == params.is_empty() {
+                let query = bucket.merge(query.as_str(), params);
+                last.push_str(query.as_str());
+                return;
+            }
+        }
+        self.parts.push((query, params));
+    }
+
+    /// Surround the fragment with raw strings
+    pub(crate) fn wrap(self, prefix: &str, suffix: &str) -> Self {
+        let mut result = Fragment::raw(prefix);
+        result.push(self);
+        result.push_str(suffix);
+        result
+    }
+
+    /// Join fragments with a separator
+    pub(crate) fn join(fragments: Vec<Fragment>, separator: &str) -> Self {
+        let mut result = Fragment::raw("");
+        for (index, fragment) in fragments.into_iter().enumerate() {
+            if index > 0 {
+                result.push_str(separator);
+            }
+            result.push(fragment);
+        }
+        result
+    }
+
+    /// Bind a parameter to the last part, numbered after its parameters
+    pub(crate) fn bind<T: 'static + ToSql + Sync + Clone>(&mut self, value: T) {
+        if let Some((_, params)) = self.parts.last_mut() {
+            params.push(value);
+        }
+    }
+
+    /// Render the fragment, numbering its parameters after `offset` ones
+    pub(crate) fn render(&self, offset: &mut usize) -> String {
+        let mut result = String::new();
+        for (query, params) in self.parts.iter() {
+            if params.is_empty() {
+                result.push_str(query.as_str());
+            } else {
+                result.push_str(shift_placeholders(query.as_str(), *offset).as_str());
+                *offset += params.len();
+            }
+        }
+        result
+    }
+
+    /// Merge the parts one after the other with `merge`, returning the query
+    pub(crate) fn merge_with<F: FnMut(&str, Bucket) -> String>(self, mut merge: F) -> String {
+        let mut result = String::new();
+        for (query, params) in self.parts {
+            result.push_str(merge(query.as_str(), params).as_str());
+        }
+        result
+    }
+
+    /// Move the parameters of the fragment into a bucket, returning the query
+    /// with its placeholders shifted accordingly
+    pub(crate) fn merge_into(self, params: &mut Bucket) -> String {
+        self.merge_with(|query, other| params.merge(query, other))
+    }
+
+    /// Get the parameters of the fragment, in the order of `render`
+    pub(crate) fn into_params(self) -> Bucket {
+        let mut params = Bucket::new();
+        self.merge_into(&mut params);
+        params
+    }
+}
+
+impl fmt::Display for Fragment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(&mut 0))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    fn param(value: i32) -> Fragment {
+        let mut params = Bucket::new();
+        params.push(value);
+        Fragment::new("$1".into(), params)
+    }
+
+    #[test]
+    fn keep_raw_parts() {
+        let fragment = Fragment::join(vec![Fragment::raw("$1"), param(2), param(3)], ", ");
+        assert_eq!(fragment.to_string(), "$1, $1, $2");
+        let mut params = Bucket::new();
+        params.push(1);
+        assert_eq!(fragment.merge_into(&mut params), "$1, $2, $3");
+        assert_eq!(params.len(), 3);
+    }
+
+    #[test]
+    fn render_after_offset() {
+        let fragment = param(1).wrap("(", ")");
+        let mut offset = 2;
+        assert_eq!(fragment.render(&mut offset), "($3)");
+        assert_eq!(offset, 3);
+    }
+}
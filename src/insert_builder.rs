@@ -0,0 +1,717 @@
+use crate::bucket::{remove_placeholders, Bucket};
+use crate::condition::Condition;
+use crate::error::Error;
+use crate::expression::Expression;
+use crate::prelude::*;
+use crate::with_query::{with_clause, WithQuery};
+use postgres_types::ToSql;
+use std::ops::Range;
+
+/// The value of a column in a row, with the range of the parameters it owns
+struct RowValue {
+    column: String,
+    value: String,
+    params: Range<usize>,
+}
+
+pub struct InsertBuilder {
+    with_queries: Vec<WithQuery>,
+    table: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<RowValue>>,
+    select: Option<(String, Range<usize>)>,
+    conflict_target: Option<String>,
+    conflict_fields: Vec<String>,
+    conflict_conditions: Vec<String>,
+    returning: Vec<String>,
+    params: Bucket,
+}
+
+impl InsertBuilder {
+    /// Create a new insert builder for a given table
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::InsertBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("name", "rick".to_string());
+    /// builder.value("age", 42);
+    ///
+    /// assert_eq!(builder.get_query(), "INSERT INTO users (name, age) VALUES ($1, $2)");
+    /// ```
+    pub fn new(table: &str) -> Self {
+        InsertBuilder {
+            with_queries: vec![],
+            table: table.into(),
+            columns: vec![],
+            rows: vec![vec![]],
+            select: None,
+            conflict_target: None,
+            conflict_fields: vec![],
+            conflict_conditions: vec![],
+            returning: vec![],
+            params: Bucket::new(),
+        }
+    }
+
+    /// Set the value of a column in the current row
+    ///
+    /// Setting a column twice in the same row replaces its value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::InsertBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("name", "rick".to_string());
+    ///
+    /// assert_eq!(builder.get_query(), "INSERT INTO users (name) VALUES ($1)");
+    /// ```
+    pub fn value<T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        column: &str,
+        value: T,
+    ) -> &mut Self {
+        self.value_computed(column, Expression::param(value))
+    }
+
+    /// Set the value of a column in the current row with an expression,
+    /// merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::InsertBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("name", "rick".to_string());
+    /// builder.value_computed("created_at", "now()");
+    ///
+    /// assert_eq!(builder.get_query(), "INSERT INTO users (name, created_at) VALUES ($1, now())");
+    /// ```
+    pub fn value_computed<E: Into<Expression>>(&mut self, column: &str, value: E) -> &mut Self {
+        if let Some((_, range)) = self.select.take() {
+            self.remove_params(range);
+            self.columns.clear();
+        }
+        if !self.columns.iter().any(|item| item == column) {
+            self.columns.push(column.into());
+        }
+        let replaced = self
+            .rows
+            .last()
+            .and_then(|row| row.iter().find(|item| item.column == column))
+            .map(|item| item.params.clone());
+        if let Some(range) = replaced {
+            self.remove_params(range);
+        }
+        let start = self.params.len() + 1;
+        let value = value.into().merge_into(&mut self.params);
+        let value = RowValue {
+            column: column.into(),
+            value,
+            params: start..self.params.len() + 1,
+        };
+        if let Some(row) = self.rows.last_mut() {
+            row.retain(|item| item.column != value.column);
+            row.push(value);
+        }
+        self
+    }
+
+    /// Start a new row of values
+    ///
+    /// Columns that are not set in a row are filled with `DEFAULT`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::InsertBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("name", "rick".to_string());
+    /// builder.value("age", 70);
+    /// builder.new_row();
+    /// builder.value("name", "morty".to_string());
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "INSERT INTO users (name, age) VALUES ($1, $2), ($3, DEFAULT)"
+    /// );
+    /// ```
+    pub fn new_row(&mut self) -> &mut Self {
+        let current_is_empty = self.rows.last().map(|row| row.is_empty()).unwrap_or(false);
+        if !current_is_empty {
+            self.rows.push(vec![]);
+        }
+        self
+    }
+
+    /// Insert the rows returned by a select query instead of values,
+    /// merging its parameters
+    ///
+    /// The select query replaces the values set before, and is itself
+    /// replaced by any value set afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{InsertBuilder, SelectBuilder};
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithWhere};
+    ///
+    /// let mut source = SelectBuilder::new("users");
+    /// source.select("id");
+    /// source.select("email");
+    /// source.where_eq("active", false);
+    /// let mut builder = InsertBuilder::new("archived_users");
+    /// builder.select(&["id", "email"], source);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "INSERT INTO archived_users (id, email) SELECT id, email FROM users WHERE active = $1"
+    /// );
+    /// ```
+    pub fn select<Q: SelectQuery>(&mut self, columns: &[&str], query: Q) -> &mut Self {
+        let mut replaced: Vec<Range<usize>> = self
+            .rows
+            .drain(..)
+            .flatten()
+            .map(|item| item.params)
+            .chain(self.select.take().map(|(_, range)| range))
+            .collect();
+        // removing the last parameters first keeps the other ranges valid
+        replaced.sort_by_key(|range| std::cmp::Reverse(range.start));
+        for range in replaced {
+            self.remove_params(range);
+        }
+        self.rows.push(vec![]);
+        let start = self.params.len() + 1;
+        let subquery = query.get_query();
+        let subquery = self.params.merge(subquery.as_str(), query.get_params());
+        self.columns = columns.iter().map(|column| column.to_string()).collect();
+        self.select = Some((subquery, start..self.params.len() + 1));
+        self
+    }
+
+    /// Handle conflicts on the given columns
+    ///
+    /// Without any assignment added with `set`, `set_computed` or `set_excluded`,
+    /// the conflicting rows are skipped with `DO NOTHING`. An empty list of
+    /// columns handles conflicts on any constraint, which is only allowed with
+    /// `DO NOTHING`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::InsertBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("email", "rick@example.com".to_string());
+    /// builder.on_conflict(&["email"]);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "INSERT INTO users (email) VALUES ($1) ON CONFLICT (email) DO NOTHING"
+    /// );
+    /// ```
+    pub fn on_conflict(&mut self, columns: &[&str]) -> &mut Self {
+        let target = if columns.is_empty() {
+            String::new()
+        } else {
+            format!("({})", columns.join(", "))
+        };
+        self.conflict_target = Some(target);
+        self
+    }
+
+    /// Handle conflicts on the given constraint
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::InsertBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("email", "rick@example.com".to_string());
+    /// builder.on_conflict_constraint("users_email_key");
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "INSERT INTO users (email) VALUES ($1) ON CONFLICT ON CONSTRAINT users_email_key DO NOTHING"
+    /// );
+    /// ```
+    pub fn on_conflict_constraint(&mut self, name: &str) -> &mut Self {
+        self.conflict_target = Some(format!("ON CONSTRAINT {}", name));
+        self
+    }
+
+    /// Update a column with the value proposed for insertion on conflict
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::InsertBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("email", "rick@example.com".to_string());
+    /// builder.value("name", "rick".to_string());
+    /// builder.on_conflict(&["email"]);
+    /// builder.set_excluded("name");
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "INSERT INTO users (email, name) VALUES ($1, $2) ON CONFLICT (email) DO UPDATE SET name = EXCLUDED.name"
+    /// );
+    /// ```
+    pub fn set_excluded(&mut self, field: &str) -> &mut Self {
+        self.set_computed(field, format!("EXCLUDED.{}", field).as_str())
+    }
+
+    /// Add a condition to the update done on conflict
+    ///
+    /// The condition is only rendered once the conflict target is set with
+    /// `on_conflict` or `on_conflict_constraint`, which can be done later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Condition, InsertBuilder};
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithSet};
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("email", "rick@example.com".to_string());
+    /// builder.on_conflict(&["email"]);
+    /// builder.set("active", true);
+    /// builder.do_update_where(Condition::eq("users.locked", false));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "INSERT INTO users (email) VALUES ($1) ON CONFLICT (email) DO UPDATE SET active = $2 WHERE users.locked = $3"
+    /// );
+    /// ```
+    pub fn do_update_where(&mut self, condition: Condition) -> &mut Self {
+        let condition = condition.merge_into(&mut self.params);
+        self.conflict_conditions.push(condition);
+        self
+    }
+
+    /// Check that the query is accepted by Postgres
+    ///
+    /// The query returned by `get_query` is not checked, this must be called
+    /// before running a query built from dynamic input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::InsertBuilder;
+    /// use postgres_querybuilder::error::Error;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithSet;
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("email", "rick@example.com".to_string());
+    /// builder.on_conflict(&[]);
+    /// builder.set("active", true);
+    ///
+    /// assert_eq!(builder.validate(), Err(Error::ConflictTargetRequired));
+    /// ```
+    pub fn validate(&self) -> Result<(), Error> {
+        let untargeted = self
+            .conflict_target
+            .as_ref()
+            .map(|target| target.is_empty())
+            .unwrap_or(false);
+        if untargeted && !self.conflict_fields.is_empty() {
+            return Err(Error::ConflictTargetRequired);
+        }
+        // the conflict update would be silently dropped along with its parameters
+        let updating = !self.conflict_fields.is_empty() || !self.conflict_conditions.is_empty();
+        if self.conflict_target.is_none() && updating {
+            return Err(Error::ConflictTargetRequired);
+        }
+        Ok(())
+    }
+}
+
+impl InsertBuilder {
+    /// Remove parameters that are no longer used, renumbering the
+    /// placeholders of the following ones
+    fn remove_params(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        self.params.remove(range.clone());
+        for query in self.with_queries.iter_mut() {
+            query.remove_params(&range);
+        }
+        for item in self.rows.iter_mut().flatten() {
+            item.value = remove_placeholders(item.value.as_str(), &range);
+            if item.params.start >= range.end {
+                item.params = item.params.start - range.len()..item.params.end - range.len();
+            }
+        }
+        if let Some((select, params)) = self.select.as_mut() {
+            *select = remove_placeholders(select.as_str(), &range);
+            if params.start >= range.end {
+                *params = params.start - range.len()..params.end - range.len();
+            }
+        }
+        let sections = self
+            .conflict_fields
+            .iter_mut()
+            .chain(self.conflict_conditions.iter_mut())
+            .chain(self.returning.iter_mut());
+        for query in sections {
+            *query = remove_placeholders(query.as_str(), &range);
+        }
+    }
+
+    fn with_queries_to_query(&self) -> Option<String> {
+        with_clause(&self.with_queries)
+    }
+
+    fn table_to_query(&self) -> String {
+        format!("INSERT INTO {}", self.table)
+    }
+
+    fn row_to_query(&self, row: &[RowValue]) -> String {
+        let values: Vec<&str> = self
+            .columns
+            .iter()
+            .map(|column| {
+                row.iter()
+                    .find(|item| &item.column == column)
+                    .map(|item| item.value.as_str())
+                    .unwrap_or("DEFAULT")
+            })
+            .collect();
+        format!("({})", values.join(", "))
+    }
+
+    fn values_to_query(&self) -> String {
+        if let Some((select, _)) = self.select.as_ref() {
+            return if self.columns.is_empty() {
+                select.clone()
+            } else {
+                format!("({}) {}", self.columns.join(", "), select)
+            };
+        }
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .filter(|row| !row.is_empty())
+            .map(|row| self.row_to_query(row))
+            .collect();
+        if !rows.is_empty() {
+            format!("({}) VALUES {}", self.columns.join(", "), rows.join(", "))
+        } else {
+            "DEFAULT VALUES".into()
+        }
+    }
+
+    fn conflict_to_query(&self) -> Option<String> {
+        let target = self.conflict_target.as_ref()?;
+        let mut result = vec!["ON CONFLICT".to_string()];
+        if !target.is_empty() {
+            result.push(target.clone());
+        }
+        if self.conflict_fields.is_empty() {
+            result.push("DO NOTHING".into());
+        } else {
+            result.push(format!("DO UPDATE SET {}", self.conflict_fields.join(", ")));
+            if !self.conflict_conditions.is_empty() {
+                result.push(format!("WHERE {}", self.conflict_conditions.join(" AND ")));
+            }
+        }
+        Some(result.join(" "))
+    }
+
+    fn returning_to_query(&self) -> Option<String> {
+        if !self.returning.is_empty() {
+            Some(format!("RETURNING {}", self.returning.join(", ")))
+        } else {
+            None
+        }
+    }
+}
+
+impl QueryBuilder for InsertBuilder {
+    fn add_param<T: 'static + ToSql + Sync + Clone>(&mut self, value: T) -> usize {
+        self.params.push(value)
+    }
+
+    fn merge_params(&mut self, query: &str, params: Bucket) -> String {
+        self.params.merge(query, params)
+    }
+
+    fn get_query(&self) -> String {
+        let mut result: Vec<String> = vec![];
+        if let Some(value) = self.with_queries_to_query() {
+            result.push(value);
+        }
+        result.push(self.table_to_query());
+        result.push(self.values_to_query());
+        if let Some(value) = self.conflict_to_query() {
+            result.push(value);
+        }
+        if let Some(value) = self.returning_to_query() {
+            result.push(value);
+        }
+        result.join(" ")
+    }
+
+    fn get_params(self) -> Bucket {
+        self.params
+    }
+
+    fn get_ref_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params.get_refs()
+    }
+}
+
+impl QueryBuilderWithSet for InsertBuilder {
+    /// Add an assignment to the update done on conflict
+    ///
+    /// The assignment is only rendered once the conflict target is set with
+    /// `on_conflict` or `on_conflict_constraint`, which can be done later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::InsertBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithSet};
+    ///
+    /// let mut builder = InsertBuilder::new("users");
+    /// builder.value("email", "rick@example.com".to_string());
+    /// builder.on_conflict(&["email"]);
+    /// builder.set("active", true);
+    /// builder.set_computed("updated_at", "now()");
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "INSERT INTO users (email) VALUES ($1) ON CONFLICT (email) DO UPDATE SET active = $2, updated_at = now()"
+    /// );
+    /// ```
+    fn set<T: 'static + ToSql + Sync + Clone>(&mut self, field: &str, value: T) -> &mut Self {
+        self.set_computed(field, Expression::param(value))
+    }
+
+    fn set_computed<E: Into<Expression>>(&mut self, field: &str, value: E) -> &mut Self {
+        let value = value.into().merge_into(&mut self.params);
+        self.conflict_fields.push(format!("{} = {}", field, value));
+        self
+    }
+}
+
+impl QueryBuilderWithReturning for InsertBuilder {
+    fn returning<E: Into<Expression>>(&mut self, column: E) -> &mut Self {
+        let column = column.into().merge_into(&mut self.params);
+        self.returning.push(column);
+        self
+    }
+}
+
+impl ModifyingQuery for InsertBuilder {}
+
+impl QueryBuilderWithQueries for InsertBuilder {
+    fn with(&mut self, query: WithQuery) -> &mut Self {
+        let query = query.merge_into(&mut self.params);
+        self.with_queries.push(query);
+        self
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::select_builder::SelectBuilder;
+
+    #[test]
+    fn from_scratch() {
+        let builder = InsertBuilder::new("publishers");
+        assert_eq!(builder.get_query(), "INSERT INTO publishers DEFAULT VALUES");
+    }
+
+    #[test]
+    fn with_values() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.value("id", 5);
+        builder.value_computed("name", "md5(42)");
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers (id, name) VALUES ($1, md5(42))"
+        );
+    }
+
+    #[test]
+    fn with_multiple_rows() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.value("id", 1);
+        builder.value("name", "first");
+        builder.new_row();
+        builder.new_row();
+        builder.value("name", "second");
+        builder.value("id", 2);
+        builder.new_row();
+        builder.value("id", 3);
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers (id, name) VALUES ($1, $2), ($4, $3), ($5, DEFAULT)"
+        );
+    }
+
+    #[test]
+    fn with_value_replaced() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.value("id", 1);
+        builder.value_computed(
+            "name",
+            Expression::param("first").concat(Expression::param("!")),
+        );
+        builder.returning(Expression::param(0));
+        builder.value("id", 2);
+        builder.value("name", "second");
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers (id, name) VALUES ($2, $3) RETURNING $1"
+        );
+        assert_eq!(builder.get_params().len(), 3);
+    }
+
+    #[test]
+    fn with_conflict_do_nothing() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.value("name", "rick");
+        builder.on_conflict(&[]);
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers (name) VALUES ($1) ON CONFLICT DO NOTHING"
+        );
+    }
+
+    #[test]
+    fn with_conflict_do_update() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.value("id", 1);
+        builder.value("name", "rick");
+        builder.on_conflict(&["id", "name"]);
+        builder.set_excluded("name");
+        builder.set("updated", true);
+        builder.do_update_where(
+            Condition::ne("publishers.name", "morty").or(Condition::is_null("publishers.name")),
+        );
+        builder.returning("id");
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers (id, name) VALUES ($1, $2) ON CONFLICT (id, name) DO UPDATE SET name = EXCLUDED.name, updated = $3 WHERE (publishers.name <> $4 OR publishers.name IS NULL) RETURNING id"
+        );
+    }
+
+    #[test]
+    fn with_update_before_conflict() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.value("name", "rick");
+        builder.set("name", "morty");
+        builder.do_update_where(Condition::eq("id", 1));
+        assert_eq!(builder.validate(), Err(Error::ConflictTargetRequired));
+        builder.on_conflict(&["name"]);
+        assert!(builder.validate().is_ok());
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers (name) VALUES ($1) ON CONFLICT (name) DO UPDATE SET name = $2 WHERE id = $3"
+        );
+        assert_eq!(builder.get_params().len(), 3);
+    }
+
+    #[test]
+    fn with_update_without_conflict_target() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.value("name", "rick");
+        builder.on_conflict(&[]);
+        assert!(builder.validate().is_ok());
+        builder.set_excluded("name");
+        assert_eq!(builder.validate(), Err(Error::ConflictTargetRequired));
+        builder.on_conflict_constraint("publishers_name_key");
+        assert!(builder.validate().is_ok());
+    }
+
+    #[test]
+    fn with_select() {
+        let mut source = SelectBuilder::new("staging");
+        source.select("id");
+        source.select("$1::text");
+        source.add_param("imported");
+        source.where_gt("id", 10);
+        let mut builder = InsertBuilder::new("publishers");
+        builder.with_query("staging", "SELECT * FROM incoming");
+        builder.select(&["id", "name"], source);
+        builder.on_conflict(&["id"]);
+        builder.set("name", "duplicate");
+        assert_eq!(
+            builder.get_query(),
+            "WITH staging AS (SELECT * FROM incoming) INSERT INTO publishers (id, name) SELECT id, $1::text FROM staging WHERE id > $2 ON CONFLICT (id) DO UPDATE SET name = $3"
+        );
+    }
+
+    #[test]
+    fn with_select_replacing_values() {
+        let mut source = SelectBuilder::new("staging");
+        source.where_gt("id", 10);
+        let mut builder = InsertBuilder::new("publishers");
+        builder.value("id", 1);
+        builder.returning(Expression::param(0));
+        builder.value("name", "rick");
+        builder.select(&["id", "name"], source);
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers (id, name) SELECT * FROM staging WHERE id > $2 RETURNING $1"
+        );
+        builder.value("name", "morty");
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers (name) VALUES ($2) RETURNING $1"
+        );
+        assert_eq!(builder.get_params().len(), 2);
+    }
+
+    #[test]
+    fn with_select_without_columns() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.select(&[], SelectBuilder::new("staging"));
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers SELECT * FROM staging"
+        );
+    }
+
+    #[test]
+    fn with_returning() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.value("name", "rick");
+        builder.returning("id");
+        assert_eq!(
+            builder.get_query(),
+            "INSERT INTO publishers (name) VALUES ($1) RETURNING id"
+        );
+    }
+
+    #[test]
+    fn with_subquery() {
+        let mut builder = InsertBuilder::new("publishers");
+        builder.with_query("archived", "SELECT * FROM archived_publishers");
+        builder.value("id", 5);
+        assert_eq!(
+            builder.get_query(),
+            "WITH archived AS (SELECT * FROM archived_publishers) INSERT INTO publishers (id) VALUES ($1)"
+        );
+    }
+}
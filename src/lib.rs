@@ -7,20 +7,38 @@
 #[macro_use]
 extern crate serial_test;
 
+mod aggregate;
 pub mod bucket;
+mod compound_builder;
+mod condition;
+mod delete_builder;
+pub mod error;
+mod expression;
+mod fragment;
+mod insert_builder;
 pub mod prelude;
 mod select_builder;
 mod update_builder;
+mod window;
+mod with_query;
 
+pub use aggregate::{Aggregate, CountAll};
+pub use compound_builder::CompoundBuilder;
+pub use condition::Condition;
+pub use delete_builder::DeleteBuilder;
+pub use expression::{Case, Expression, Literal};
+pub use insert_builder::InsertBuilder;
 pub use select_builder::SelectBuilder;
 pub use update_builder::UpdateBuilder;
+pub use window::{FrameBound, Window, WindowFunction};
+pub use with_query::{Materialization, WithQuery};
 
 #[cfg(test)]
 mod test {
   use super::prelude::*;
   use super::select_builder::SelectBuilder;
   use super::*;
-  use postgres::{Client, Error, NoTls};
+  use postgres::{Client, Error, NoTls, Row};
   use std::env;
 
   fn get_url() -> String {
@@ -31,6 +49,7 @@ mod test {
   }
 
   fn prepare(client: &mut Client) {
+    client.execute("DROP TABLE IF EXISTS articles;", &[]).unwrap();
     client.execute("DROP TABLE IF EXISTS users;", &[]).unwrap();
     client
       .execute(
@@ -38,6 +57,21 @@ mod test {
         &[],
       )
       .unwrap();
+    client
+      .execute(
+        "CREATE TABLE articles (id SERIAL PRIMARY KEY, author_id INTEGER REFERENCES users(id), title TEXT NOT NULL);",
+        &[],
+      )
+      .unwrap();
+  }
+
+  fn seed(client: &mut Client) {
+    client
+      .batch_execute(
+        "INSERT INTO users (id, name) VALUES (1, 'rick'), (2, 'morty'), (3, 'summer');
+        INSERT INTO articles (author_id, title) VALUES (1, 'portal gun'), (1, 'pickle'), (2, 'jessica');",
+      )
+      .unwrap();
   }
 
   fn get_connection() -> Client {
@@ -50,8 +84,16 @@ mod test {
   fn execute<T: QueryBuilder>(builder: T) -> Result<u64, Error> {
     let mut client = get_connection();
     let stmt = builder.get_query();
+    let params = builder.get_params();
+    client.execute(stmt.as_str(), &params.get_refs())
+  }
+
+  fn query<T: QueryBuilder>(builder: T) -> Result<Vec<Row>, Error> {
+    let mut client = get_connection();
+    seed(&mut client);
+    let stmt = builder.get_query();
     let params = builder.get_ref_params();
-    client.execute(stmt.as_str(), &params)
+    client.query(stmt.as_str(), &params)
   }
 
   #[serial]
@@ -74,4 +116,688 @@ mod test {
     builder.where_eq("id", 42);
     execute(builder).unwrap();
   }
+
+  #[serial]
+  #[test]
+  fn select_where_condition_tree() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("name");
+    builder.where_cond(
+      Condition::eq("name", "rick".to_string())
+        .or(Condition::eq("name", "morty".to_string()))
+        .and(!Condition::eq("id", 2)),
+    );
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["rick"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_where_comparisons() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("name");
+    builder.where_between("id", 1, 3);
+    builder.where_gt("id", 1);
+    builder.where_is_not_null("name");
+    builder.where_ilike("name", "%U%".to_string());
+    builder.where_regex("name", "^s".to_string());
+    builder.where_distinct("name", "rick".to_string());
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["summer"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_where_in() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("name");
+    builder.where_in("id", vec![1, 2, 3]);
+    builder.where_not_in("id", vec![2]);
+    builder.where_eq_any("name", vec!["rick".to_string(), "summer".to_string()]);
+    builder.where_ne_all("id", vec![3]);
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["rick"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_where_in_empty() {
+    let mut builder = SelectBuilder::new("users");
+    builder.where_in::<_, i32>("id", vec![]);
+    assert_eq!(query(builder).unwrap().len(), 0);
+    let mut builder = SelectBuilder::new("users");
+    builder.where_not_in::<_, i32>("id", vec![]);
+    assert_eq!(query(builder).unwrap().len(), 3);
+  }
+
+  #[serial]
+  #[test]
+  fn select_subqueries() {
+    let mut authors = SelectBuilder::new("articles");
+    authors.select("author_id");
+    authors.where_ne("title", "jessica".to_string());
+    let mut writers = SelectBuilder::new("users");
+    writers.where_in_select("id", authors);
+    let mut builder = SelectBuilder::from_subquery(writers, "writers");
+    builder.select("writers.name");
+    builder.where_gt("writers.id", 0);
+    let mut articles = SelectBuilder::new("articles");
+    articles.where_condition("articles.author_id = writers.id");
+    articles.where_eq("articles.title", "pickle".to_string());
+    builder.where_exists(articles);
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["rick"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_with_select() {
+    let mut writers = SelectBuilder::new("articles");
+    writers.select("DISTINCT author_id");
+    writers.where_ne("title", "pickle".to_string());
+    let mut builder = SelectBuilder::new("users");
+    builder.select("name");
+    builder.where_ne("name", "rick".to_string());
+    builder.with_select("writers", writers);
+    builder.where_condition("id IN (SELECT author_id FROM writers)");
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["morty"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_with_recursive() {
+    let mut base = SelectBuilder::new("users");
+    base.select("id");
+    base.where_eq("id", 1);
+    let mut step = SelectBuilder::new("users");
+    step.select("users.id");
+    step.inner_join("chain", "users.id = chain.id + 1");
+    step.where_lte("users.id", 2);
+    let mut builder = SelectBuilder::new("chain");
+    builder.select("id");
+    builder.with_recursive_select("chain", &["id"], base, step);
+    builder.order_by(Order::Asc("id".into()));
+    let rows = query(builder).unwrap();
+    let ids: Vec<i32> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(ids, vec![1, 2]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_with_recursive_ordered_base() {
+    let mut base = SelectBuilder::new("users");
+    base.select("id");
+    base.order_by(Order::Desc("id".into()));
+    base.limit(1);
+    let mut step = SelectBuilder::new("users");
+    step.select("users.id");
+    step.inner_join("chain", "users.id = chain.id - 1");
+    let mut builder = SelectBuilder::new("chain");
+    builder.select("id");
+    builder.with_recursive_select("chain", &["id"], base, step);
+    let rows = query(builder).unwrap();
+    let ids: Vec<i32> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(ids, vec![3, 2, 1]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_with_materialized() {
+    let mut writers = SelectBuilder::new("articles");
+    writers.select("author_id");
+    writers.where_eq("title", "pickle".to_string());
+    let mut builder = SelectBuilder::new("users");
+    builder.select("name");
+    builder.with(WithQuery::select("writers", writers).materialized());
+    builder.where_condition("id IN (SELECT author_id FROM writers)");
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["rick"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_having() {
+    let mut builder = SelectBuilder::new("articles");
+    builder.select("author_id");
+    builder.group_by("author_id");
+    builder.having_gt("count(*)", 1i64);
+    let rows = query(builder).unwrap();
+    let ids: Vec<i32> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(ids, vec![1]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_grouping_sets() {
+    let mut builder = SelectBuilder::new("articles");
+    builder.select("author_id");
+    builder.select("count(*)");
+    builder.select_grouping(&["author_id"]);
+    builder.group_by_sets(&[&["author_id"], &[]]);
+    builder.order_by(Order::Asc("author_id".into()));
+    let rows = query(builder).unwrap();
+    let totals: Vec<(Option<i32>, i64, i32)> = rows
+      .iter()
+      .map(|row| (row.get(0), row.get(1), row.get(2)))
+      .collect();
+    assert_eq!(totals, vec![(Some(1), 2, 0), (Some(2), 1, 0), (None, 3, 1)]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_rollup() {
+    let mut builder = SelectBuilder::new("articles");
+    builder.select("count(*)");
+    builder.group_by_rollup(&["author_id", "title"]);
+    assert_eq!(query(builder).unwrap().len(), 6);
+  }
+
+  #[serial]
+  #[test]
+  fn select_distinct_on() {
+    let mut builder = SelectBuilder::new("articles");
+    builder.select("author_id");
+    builder.select("title");
+    builder.distinct_on("author_id");
+    builder.order_by(Order::Asc("author_id".into()));
+    builder.order_by(Order::Desc("title".into()));
+    builder.validate().unwrap();
+    let rows = query(builder).unwrap();
+    let titles: Vec<String> = rows.iter().map(|row| row.get(1)).collect();
+    assert_eq!(titles, vec!["portal gun", "jessica"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_distinct() {
+    let mut builder = SelectBuilder::new("articles");
+    builder.select("author_id");
+    builder.distinct();
+    assert_eq!(query(builder).unwrap().len(), 2);
+  }
+
+  #[serial]
+  #[test]
+  fn select_rich_order() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("users.name");
+    builder.left_join("articles", "articles.author_id = users.id");
+    builder
+      .order_by(Ordering::new(Order::Asc("articles.title".into())).nulls(Nulls::First))
+      .order_by(Ordering::new(Order::Desc("users.name".into())).collate("C"))
+      .order_by(Ordering::new(Order::Asc("abs(users.id - $1)".into())).bind(2));
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["summer", "morty", "rick", "rick"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_order_by_expression() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("name");
+    builder.where_ne("name", "morty".to_string());
+    builder.order_by(Ordering::new(Order::Asc("abs(id - $1)".into())).bind(3));
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["summer", "rick"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_compound() {
+    let mut first = SelectBuilder::new("users");
+    first.select("name");
+    first.where_lte("id", 2);
+    let mut second = SelectBuilder::new("users");
+    second.select("name");
+    second.where_gte("id", 2);
+    let mut third = SelectBuilder::new("users");
+    third.select("name");
+    third.where_eq("name", "summer".to_string());
+    let mut builder = CompoundBuilder::new(first);
+    builder.union_all(second);
+    builder.except(third);
+    builder.order_by(Order::Desc("name".into()));
+    builder.limit(5);
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["rick", "morty"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_from_compound() {
+    let mut first = SelectBuilder::new("users");
+    first.select("id");
+    first.where_eq("name", "rick".to_string());
+    let mut second = SelectBuilder::new("users");
+    second.select("id");
+    second.where_eq("name", "morty".to_string());
+    let mut compound = CompoundBuilder::new(first);
+    compound.intersect(second);
+    let mut builder = SelectBuilder::new("articles");
+    builder.where_not_in_select("author_id", compound);
+    assert_eq!(query(builder).unwrap().len(), 3);
+  }
+
+  #[serial]
+  #[test]
+  fn select_for_update_skip_locked() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("id");
+    builder.where_gt("id", 1);
+    builder.order_by(Order::Asc("id".into()));
+    builder.limit(1);
+    builder.lock(Lock::update().of("users").skip_locked());
+    let rows = query(builder).unwrap();
+    let ids: Vec<i32> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(ids, vec![2]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_expressions() {
+    let mut count = SelectBuilder::new("articles");
+    count.select("count(*)");
+    count.where_condition("articles.author_id = users.id");
+    count.where_ne("title", "pickle".to_string());
+    let mut builder = SelectBuilder::new("users");
+    builder.select(Expression::column("name").concat(Expression::param(":".to_string())));
+    builder.select(Expression::from(
+      Case::new(
+        Expression::select(count).gt(Expression::param(0i64)),
+        Expression::literal("writer"),
+      )
+      .otherwise(Expression::param("reader".to_string())),
+    ));
+    builder.where_cond(Expression::column("id").lte(Expression::param(2).cast("integer")));
+    builder.order_by(Ordering::asc(
+      Expression::column("id") * Expression::param(-1),
+    ));
+    let rows = query(builder).unwrap();
+    let values: Vec<(String, String)> = rows.iter().map(|row| (row.get(0), row.get(1))).collect();
+    assert_eq!(
+      values,
+      vec![
+        ("morty:".to_string(), "writer".to_string()),
+        ("rick:".to_string(), "writer".to_string()),
+      ]
+    );
+  }
+
+  #[serial]
+  #[test]
+  fn select_aggregates_and_windows() {
+    let mut builder = SelectBuilder::new("articles");
+    builder.select("author_id");
+    builder.select(Aggregate::count_all().filter(Condition::ne("title", "pickle".to_string())));
+    builder.select(
+      Aggregate::new(
+        "string_agg",
+        vec![
+          Expression::column("title"),
+          Expression::param(", ".to_string()),
+        ],
+      )
+      .distinct()
+      .order_by(Order::Asc("title".into())),
+    );
+    builder.select(WindowFunction::row_number().over_named("w"));
+    builder.select(
+      Aggregate::sum(Expression::raw("count(*)"))
+        .over(Window::extend("w").rows(FrameBound::UnboundedPreceding, FrameBound::CurrentRow))
+        .cast("bigint"),
+    );
+    builder.group_by("author_id");
+    builder.window("w", Window::new().order_by(Order::Asc("author_id".into())));
+    builder.order_by(Order::Asc("author_id".into()));
+    let rows = query(builder).unwrap();
+    let values: Vec<(i32, i64, String, i64, i64)> = rows
+      .iter()
+      .map(|row| (row.get(0), row.get(1), row.get(2), row.get(3), row.get(4)))
+      .collect();
+    assert_eq!(
+      values,
+      vec![
+        (1, 1, "pickle, portal gun".to_string(), 1, 2),
+        (2, 1, "jessica".to_string(), 2, 3),
+      ]
+    );
+  }
+
+  #[serial]
+  #[test]
+  fn select_lag_lead() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("name");
+    builder.select(WindowFunction::lag(Expression::column("name"), 1).over_named("w"));
+    builder.select(WindowFunction::lead(Expression::column("name"), 1).over_named("w"));
+    builder.window("w", Window::new().order_by(Order::Asc("id".into())));
+    builder.order_by(Order::Asc("id".into()));
+    let rows = query(builder).unwrap();
+    let values: Vec<(String, Option<String>, Option<String>)> = rows
+      .iter()
+      .map(|row| (row.get(0), row.get(1), row.get(2)))
+      .collect();
+    assert_eq!(
+      values[1],
+      (
+        "morty".to_string(),
+        Some("rick".to_string()),
+        Some("summer".to_string())
+      )
+    );
+  }
+
+  #[serial]
+  #[test]
+  fn select_aliases() {
+    let mut builder = SelectBuilder::new("users");
+    builder.where_eq("id", 1);
+    builder.select_as(Expression::param("tag".to_string()).cast("text"), "tag");
+    builder.select_as(
+      Expression::function(
+        "coalesce",
+        vec![
+          Expression::column("name"),
+          Expression::param("?".to_string()),
+        ],
+      ),
+      "User Name",
+    );
+    builder.select(Expression::column("id") + Expression::param(1));
+    let rows = query(builder).unwrap();
+    let names: Vec<&str> = rows[0]
+      .columns()
+      .iter()
+      .map(|column| column.name())
+      .collect();
+    assert_eq!(names, vec!["tag", "User Name", "?column?"]);
+    let tag: String = rows[0].get("tag");
+    let name: String = rows[0].get("User Name");
+    let next: i32 = rows[0].get(2);
+    assert_eq!((tag.as_str(), name.as_str(), next), ("tag", "rick", 2));
+  }
+
+  #[serial]
+  #[test]
+  fn select_inner_join() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("users.name");
+    builder.select("articles.title");
+    builder.inner_join("articles", "articles.author_id = users.id");
+    builder.where_eq("users.id", 1);
+    builder.order_by(Order::Asc("articles.title".into()));
+    let rows = query(builder).unwrap();
+    let titles: Vec<String> = rows.iter().map(|row| row.get(1)).collect();
+    assert_eq!(titles, vec!["pickle", "portal gun"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_left_join() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("users.name");
+    builder.select("articles.title");
+    builder.left_join("articles", "articles.author_id = users.id");
+    builder.where_eq("users.id", 3);
+    let rows = query(builder).unwrap();
+    assert_eq!(rows.len(), 1);
+    let title: Option<String> = rows[0].get(1);
+    assert_eq!(title, None);
+  }
+
+  #[serial]
+  #[test]
+  fn select_left_outer_join() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("users.name");
+    builder.left_outer_join("articles", "articles.author_id = users.id");
+    let rows = query(builder).unwrap();
+    assert_eq!(rows.len(), 4);
+  }
+
+  #[serial]
+  #[test]
+  fn select_right_join_using() {
+    let mut builder = SelectBuilder::new("articles");
+    builder.select("users.name");
+    builder.join(
+      Join::right("users")
+        .on("users.id = articles.author_id")
+        .on_condition(Condition::ne("articles.title", "pickle".to_string())),
+    );
+    builder.order_by(Order::Asc("users.id".into()));
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["rick", "morty", "summer"]);
+  }
+
+  #[serial]
+  #[test]
+  fn select_cross_natural_and_using_join() {
+    let mut builder = SelectBuilder::new("users");
+    builder.select("count(*)");
+    builder.join(Join::inner("users").alias("same").natural());
+    builder.join(Join::full_outer("users").alias("copies").using(&["name"]));
+    builder.cross_join("articles");
+    let rows = query(builder).unwrap();
+    let count: i64 = rows[0].get(0);
+    assert_eq!(count, 9);
+  }
+
+  #[serial]
+  #[test]
+  fn select_left_join_lateral() {
+    let mut latest = SelectBuilder::new("articles");
+    latest.select("title");
+    latest.where_condition("articles.author_id = users.id");
+    latest.where_ne("title", "pickle".to_string());
+    latest.order_by(Order::Asc("title".into()));
+    latest.limit(1);
+    let mut builder = SelectBuilder::new("users");
+    builder.select("users.name");
+    builder.select("latest.title");
+    builder.left_join_lateral(latest, "latest");
+    builder.join(
+      Join::cross("generate_series(1, $1)")
+        .bind(2)
+        .alias("n")
+        .lateral(),
+    );
+    builder.where_lte("users.id", 2);
+    builder.where_eq("n", 1);
+    builder.order_by(Order::Asc("users.id".into()));
+    let rows = query(builder).unwrap();
+    let titles: Vec<Option<String>> = rows.iter().map(|row| row.get(1)).collect();
+    assert_eq!(
+      titles,
+      vec![Some("portal gun".to_string()), Some("jessica".to_string())]
+    );
+  }
+
+  #[serial]
+  #[test]
+  fn update_returning() {
+    let mut builder = UpdateBuilder::new("users");
+    builder.set("name", "squanchy".to_string());
+    builder.where_eq("id", 2);
+    builder.returning("id");
+    builder.returning("name");
+    let rows = query(builder).unwrap();
+    assert_eq!(rows.len(), 1);
+    let id: i32 = rows[0].get(0);
+    let name: String = rows[0].get(1);
+    assert_eq!(id, 2);
+    assert_eq!(name, "squanchy");
+  }
+
+  #[serial]
+  #[test]
+  fn update_with_recursive() {
+    let mut base = SelectBuilder::new("users");
+    base.select("id");
+    base.where_eq("id", 3);
+    let mut step = SelectBuilder::new("users");
+    step.select("users.id");
+    step.inner_join("chain", "users.id = chain.id - 1");
+    step.where_gte("users.id", 2);
+    let mut builder = UpdateBuilder::new("users");
+    builder.with_recursive_select("chain", &["id"], base, step);
+    builder.set_computed("name", "upper(name)");
+    builder.where_condition("id IN (SELECT id FROM chain)");
+    builder.returning("id");
+    let rows = query(builder).unwrap();
+    let mut ids: Vec<i32> = rows.iter().map(|row| row.get(0)).collect();
+    ids.sort();
+    assert_eq!(ids, vec![2, 3]);
+  }
+
+  #[serial]
+  #[test]
+  fn update_from() {
+    let mut titles = SelectBuilder::new("articles");
+    titles.select("author_id");
+    titles.select("min(title) AS title");
+    titles.where_ne("title", "pickle".to_string());
+    titles.group_by("author_id");
+    let mut builder = UpdateBuilder::new("users");
+    builder.set_computed("name", "users.name || ' ' || titles.title");
+    builder.from_select(titles, "titles");
+    builder.where_condition("titles.author_id = users.id");
+    builder.returning("users.name");
+    let rows = query(builder).unwrap();
+    let mut names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    names.sort();
+    assert_eq!(names, vec!["morty jessica", "rick portal gun"]);
+  }
+
+  #[serial]
+  #[test]
+  fn insert_returning() {
+    let mut builder = InsertBuilder::new("users");
+    builder.value("id", 10);
+    builder.value("name", "jerry".to_string());
+    builder.returning("id");
+    let rows = query(builder).unwrap();
+    let ids: Vec<i32> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(ids, vec![10]);
+  }
+
+  #[serial]
+  #[test]
+  fn insert_on_conflict_do_nothing() {
+    let mut builder = InsertBuilder::new("users");
+    builder.value("id", 10);
+    builder.value("name", "rick".to_string());
+    builder.new_row();
+    builder.value("id", 11);
+    builder.value("name", "jerry".to_string());
+    builder.on_conflict(&["name"]);
+    builder.returning("name");
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["jerry"]);
+  }
+
+  #[serial]
+  #[test]
+  fn insert_on_conflict_do_update() {
+    let mut builder = InsertBuilder::new("users");
+    builder.value("id", 1);
+    builder.value("name", "pickle rick".to_string());
+    builder.new_row();
+    builder.value("id", 2);
+    builder.value("name", "evil morty".to_string());
+    builder.on_conflict_constraint("users_pkey");
+    builder.set_excluded("name");
+    builder.do_update_where(Condition::ne("users.name", "morty".to_string()));
+    builder.returning("name");
+    let rows = query(builder).unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["pickle rick"]);
+  }
+
+  #[serial]
+  #[test]
+  fn insert_select() {
+    let mut source = SelectBuilder::new("users");
+    source.select("id");
+    source.select("name || $1");
+    source.add_param(" archive".to_string());
+    source.where_ne("name", "rick".to_string());
+    let mut builder = InsertBuilder::new("articles");
+    builder.select(&["author_id", "title"], source);
+    builder.returning("title");
+    let rows = query(builder).unwrap();
+    let mut titles: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    titles.sort();
+    assert_eq!(titles, vec!["morty archive", "summer archive"]);
+  }
+
+  #[serial]
+  #[test]
+  fn insert_with_modifying() {
+    let mut moved = DeleteBuilder::new("articles");
+    moved.where_eq("author_id", 1);
+    moved.returning("author_id");
+    moved.returning("title");
+    let mut source = SelectBuilder::new("moved");
+    source.select("author_id");
+    source.select("upper(title)");
+    let mut builder = InsertBuilder::new("articles");
+    builder.with_modifying("moved", moved);
+    builder.select(&["author_id", "title"], source);
+    builder.returning("title");
+    let rows = query(builder).unwrap();
+    let mut titles: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    titles.sort();
+    assert_eq!(titles, vec!["PICKLE", "PORTAL GUN"]);
+  }
+
+  #[serial]
+  #[test]
+  fn insert_values() {
+    let mut builder = InsertBuilder::new("users");
+    builder.value("name", "rick".to_string());
+    assert_eq!(execute(builder).unwrap(), 1);
+  }
+
+  #[serial]
+  #[test]
+  fn insert_multiple_rows() {
+    let mut builder = InsertBuilder::new("users");
+    builder.value("name", "rick".to_string());
+    builder.new_row();
+    builder.value("name", "morty".to_string());
+    builder.value("id", 42);
+    assert_eq!(execute(builder).unwrap(), 2);
+  }
+
+  #[serial]
+  #[test]
+  fn delete_where() {
+    let mut builder = DeleteBuilder::new("users");
+    builder.where_eq("id", 42);
+    builder.returning("id");
+    execute(builder).unwrap();
+  }
+
+  #[serial]
+  #[test]
+  fn delete_returning() {
+    let mut builder = DeleteBuilder::new("articles");
+    builder.where_eq("author_id", 1);
+    builder.returning("title");
+    let rows = query(builder).unwrap();
+    assert_eq!(rows.len(), 2);
+  }
 }
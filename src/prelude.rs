@@ -1,29 +1,360 @@
+use crate::bucket::Bucket;
+use crate::condition::Condition;
+use crate::error::Error;
+use crate::expression::Expression;
+use crate::fragment::Fragment;
+use crate::with_query::WithQuery;
 use postgres_types::ToSql;
+use std::fmt;
 
-pub enum Join {
-    Inner(String, String),
-    Left(String, String),
-    LeftOuter(String, String),
+pub enum JoinKind {
+    Inner,
+    Left,
+    LeftOuter,
+    Right,
+    RightOuter,
+    Full,
+    FullOuter,
+    Cross,
 }
 
-impl Join {
-    pub fn to_string(&self) -> String {
+impl fmt::Display for JoinKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Join::Inner(table, constraint) => format!("INNER JOIN {} ON {}", table, constraint),
-            Join::Left(table, constraint) => format!("LEFT JOIN {} ON {}", table, constraint),
-            Join::LeftOuter(table, constraint) => {
-                format!("LEFT OUTER JOIN {} ON {}", table, constraint)
+            JoinKind::Inner => write!(f, "INNER JOIN"),
+            JoinKind::Left => write!(f, "LEFT JOIN"),
+            JoinKind::LeftOuter => write!(f, "LEFT OUTER JOIN"),
+            JoinKind::Right => write!(f, "RIGHT JOIN"),
+            JoinKind::RightOuter => write!(f, "RIGHT OUTER JOIN"),
+            JoinKind::Full => write!(f, "FULL JOIN"),
+            JoinKind::FullOuter => write!(f, "FULL OUTER JOIN"),
+            JoinKind::Cross => write!(f, "CROSS JOIN"),
+        }
+    }
+}
+
+enum JoinConstraint {
+    Nothing,
+    On(Vec<Fragment>),
+    Using(Vec<String>),
+    Natural,
+}
+
+/// A joined table with its alias, join condition and parameters
+///
+/// The placeholders of the join condition are numbered from `$1` against
+/// the join parameters and are shifted when added to a builder.
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::Condition;
+/// use postgres_querybuilder::prelude::Join;
+///
+/// let join = Join::left("articles")
+///     .alias("a")
+///     .on("a.author_id = users.id")
+///     .on_condition(Condition::eq("a.published", true));
+///
+/// assert_eq!(
+///     join.to_string(),
+///     "LEFT JOIN articles AS a ON a.author_id = users.id AND a.published = $1"
+/// );
+/// ```
+pub struct Join {
+    kind: JoinKind,
+    table: Fragment,
+    lateral: bool,
+    alias: Option<String>,
+    constraint: JoinConstraint,
+}
+
+impl Join {
+    pub fn new<E: Into<Expression>>(kind: JoinKind, table: E) -> Self {
+        Join {
+            kind,
+            table: table.into().into_fragment(),
+            lateral: false,
+            alias: None,
+            constraint: JoinConstraint::Nothing,
+        }
+    }
+
+    /// Create a join against a select query, merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut latest = SelectBuilder::new("articles");
+    /// latest.select("title");
+    /// latest.where_condition("articles.author_id = users.id");
+    /// latest.order_by(Order::Desc("created_at".into()));
+    /// latest.limit(3);
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_eq("users.active", true);
+    /// builder.join(Join::select(JoinKind::Left, latest, "latest").lateral().on("true"));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users LEFT JOIN LATERAL (SELECT title FROM articles WHERE articles.author_id = users.id ORDER BY created_at DESC LIMIT $2) AS latest ON true WHERE users.active = $1"
+    /// );
+    /// ```
+    pub fn select<Q: SelectQuery>(kind: JoinKind, query: Q, alias: &str) -> Self {
+        Join::new(kind, Expression::select(query)).alias(alias)
+    }
+
+    pub fn inner<E: Into<Expression>>(table: E) -> Self {
+        Join::new(JoinKind::Inner, table)
+    }
+
+    pub fn left<E: Into<Expression>>(table: E) -> Self {
+        Join::new(JoinKind::Left, table)
+    }
+
+    pub fn left_outer<E: Into<Expression>>(table: E) -> Self {
+        Join::new(JoinKind::LeftOuter, table)
+    }
+
+    pub fn right<E: Into<Expression>>(table: E) -> Self {
+        Join::new(JoinKind::Right, table)
+    }
+
+    pub fn right_outer<E: Into<Expression>>(table: E) -> Self {
+        Join::new(JoinKind::RightOuter, table)
+    }
+
+    pub fn full<E: Into<Expression>>(table: E) -> Self {
+        Join::new(JoinKind::Full, table)
+    }
+
+    pub fn full_outer<E: Into<Expression>>(table: E) -> Self {
+        Join::new(JoinKind::FullOuter, table)
+    }
+
+    /// Join every row of a table, without any condition
+    pub fn cross<E: Into<Expression>>(table: E) -> Self {
+        Join::new(JoinKind::Cross, table)
+    }
+
+    /// Set the alias of the joined table
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Allow the joined source to refer to the columns of the preceding tables
+    pub fn lateral(mut self) -> Self {
+        self.lateral = true;
+        self
+    }
+
+    /// Bind a parameter to the joined source, such as the argument of a
+    /// set returning function
+    ///
+    /// The bound parameters are numbered before the ones of the `ON`
+    /// conditions, whether they are bound before or after the conditions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_eq("users.active", true);
+    /// builder.join(
+    ///     Join::cross("generate_series(users.created_at, now(), $1::interval)")
+    ///         .bind("1 month".to_string())
+    ///         .alias("month")
+    ///         .lateral(),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users CROSS JOIN LATERAL generate_series(users.created_at, now(), $2::interval) AS month WHERE users.active = $1"
+    /// );
+    /// ```
+    pub fn bind<T: 'static + ToSql + Sync + Clone>(mut self, value: T) -> Self {
+        self.table.bind(value);
+        self
+    }
+
+    /// Add a raw condition to the `ON` clause, joined with `AND`
+    pub fn on(self, raw: &str) -> Self {
+        self.on_fragment(Fragment::raw(raw))
+    }
+
+    fn on_fragment(mut self, condition: Fragment) -> Self {
+        match self.constraint {
+            JoinConstraint::On(ref mut conditions) => conditions.push(condition),
+            _ => self.constraint = JoinConstraint::On(vec![condition]),
+        }
+        self
+    }
+
+    /// Add a condition to the `ON` clause, merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::Condition;
+    /// use postgres_querybuilder::prelude::Join;
+    ///
+    /// let join = Join::inner("articles")
+    ///     .on_condition(Condition::gt("articles.views", 100))
+    ///     .on_condition(Condition::eq("articles.lang", "en".to_string()).or(Condition::is_null("articles.lang")));
+    ///
+    /// assert_eq!(
+    ///     join.to_string(),
+    ///     "INNER JOIN articles ON articles.views > $1 AND (articles.lang = $2 OR articles.lang IS NULL)"
+    /// );
+    /// ```
+    pub fn on_condition(self, condition: Condition) -> Self {
+        self.on_fragment(condition.into_operand())
+    }
+
+    /// Join on the equality of columns having the same name in both tables
+    pub fn using(mut self, columns: &[&str]) -> Self {
+        self.constraint =
+            JoinConstraint::Using(columns.iter().map(|column| column.to_string()).collect());
+        self
+    }
+
+    /// Join on the equality of all the columns having the same name in both tables
+    pub fn natural(mut self) -> Self {
+        self.constraint = JoinConstraint::Natural;
+        self
+    }
+
+    /// Check that the join is accepted by Postgres
+    ///
+    /// Cross joins can't have a constraint while the other joins require one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::error::Error;
+    /// use postgres_querybuilder::prelude::Join;
+    ///
+    /// assert_eq!(Join::inner("articles").validate(), Err(Error::JoinConstraintRequired));
+    /// assert_eq!(Join::cross("articles").natural().validate(), Err(Error::JoinConstraintNotAllowed));
+    /// assert!(Join::cross("articles").validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), Error> {
+        match (&self.kind, &self.constraint) {
+            (JoinKind::Cross, JoinConstraint::Nothing) => Ok(()),
+            (JoinKind::Cross, _) => Err(Error::JoinConstraintNotAllowed),
+            (_, JoinConstraint::Nothing) => Err(Error::JoinConstraintRequired),
+            _ => Ok(()),
+        }
+    }
+
+    /// Move the parameters of the join into a bucket
+    pub(crate) fn merge_into(self, params: &mut Bucket) -> Self {
+        let table = Fragment::raw(self.table.merge_into(params).as_str());
+        let constraint = match self.constraint {
+            JoinConstraint::On(conditions) => JoinConstraint::On(
+                conditions
+                    .into_iter()
+                    .map(|condition| Fragment::raw(condition.merge_into(params).as_str()))
+                    .collect(),
+            ),
+            constraint => constraint,
+        };
+        Join {
+            table,
+            constraint,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Join {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let JoinConstraint::Natural = self.constraint {
+            write!(f, "NATURAL ")?;
+        }
+        write!(f, "{} ", self.kind)?;
+        if self.lateral {
+            write!(f, "LATERAL ")?;
+        }
+        // the conditions are numbered after the parameters of the joined
+        // source, the way they are once merged into a builder
+        let mut offset = 0;
+        write!(f, "{}", self.table.render(&mut offset))?;
+        if let Some(alias) = self.alias.as_ref() {
+            write!(f, " AS {}", alias)?;
+        }
+        match &self.constraint {
+            JoinConstraint::On(conditions) => {
+                let conditions: Vec<String> = conditions
+                    .iter()
+                    .map(|condition| condition.render(&mut offset))
+                    .collect();
+                write!(f, " ON {}", conditions.join(" AND "))
             }
+            JoinConstraint::Using(columns) => write!(f, " USING ({})", columns.join(", ")),
+            _ => Ok(()),
         }
     }
 }
 
 pub trait QueryBuilder {
     fn add_param<T: 'static + ToSql + Sync + Clone>(&mut self, value: T) -> usize;
+    /// Merge parameters numbered from `$1` in `query` into the builder,
+    /// returning the query with its placeholders shifted accordingly
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::bucket::Bucket;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.add_param(42);
+    /// let mut params = Bucket::new();
+    /// params.push("rick".to_string());
+    ///
+    /// assert_eq!(builder.merge_params("name = $1", params), "name = $2");
+    /// ```
+    fn merge_params(&mut self, query: &str, params: Bucket) -> String;
+    /// Get the query
+    ///
+    /// The query is not checked against the rules that Postgres enforces on
+    /// combinations of clauses, builders having a `validate` method must be
+    /// validated before running the query.
     fn get_query(&self) -> String;
-    fn get_ref_params(self) -> Vec<&'static (dyn ToSql + Sync)>;
+    /// Get the parameters of the query, consuming the builder
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_eq("id", 42);
+    /// let query = builder.get_query();
+    /// let params = builder.get_params();
+    ///
+    /// assert_eq!(params.get_refs().len(), 1);
+    /// ```
+    fn get_params(self) -> Bucket;
+    /// Get references to the parameters of the query, borrowed from the builder
+    fn get_ref_params(&self) -> Vec<&(dyn ToSql + Sync)>;
 }
 
+/// A query returning rows that can be embedded as a subquery
+pub trait SelectQuery: QueryBuilder {}
+
+/// A query modifying rows that can be embedded as a `WITH` query
+pub trait ModifyingQuery: QueryBuilder {}
+
 pub trait QueryBuilderWithWhere: QueryBuilder {
     /// Add where condition to query
     ///
@@ -45,6 +376,32 @@ pub trait QueryBuilderWithWhere: QueryBuilder {
     /// ```
     fn where_condition(&mut self, raw: &str) -> &mut Self;
 
+    /// Add a composed condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Condition, SelectBuilder};
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_eq("active", true);
+    /// builder.where_cond(Condition::eq("age", 18).or(!Condition::eq("name", "rick".to_string())));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users WHERE active = $1 AND (age = $2 OR NOT (name = $3))"
+    /// );
+    /// ```
+    fn where_cond(&mut self, condition: Condition) -> &mut Self {
+        let condition = condition
+            .into_operand()
+            .merge_with(|query, params| self.merge_params(query, params));
+        self.where_condition(condition.as_str());
+        self
+    }
+
     /// Add where equal condition to query
     ///
     /// # Examples
@@ -60,11 +417,12 @@ pub trait QueryBuilderWithWhere: QueryBuilder {
     ///
     /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE id = $1");
     /// ```
-    fn where_eq<T: 'static + ToSql + Sync + Clone>(&mut self, field: &str, value: T) -> &mut Self {
-        let index = self.add_param(value);
-        let condition = format!("{} = ${}", field, index);
-        self.where_condition(condition.as_str());
-        self
+    fn where_eq<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::eq(field, value))
     }
 
     /// Add where not equal condition to query
@@ -82,55 +440,1480 @@ pub trait QueryBuilderWithWhere: QueryBuilder {
     ///
     /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE id <> $1");
     /// ```
-    fn where_ne<T: 'static + ToSql + Sync + Clone>(&mut self, field: &str, value: T) -> &mut Self {
-        let index = self.add_param(value);
-        let condition = format!("{} <> ${}", field, index);
-        self.where_condition(condition.as_str());
-        self
+    fn where_ne<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::ne(field, value))
     }
-}
-
-pub trait QueryBuilderWithGroupBy {
-    fn group_by(&mut self, field: &str) -> &mut Self;
-}
 
-pub trait QueryBuilderWithLimit {
-    fn limit(&mut self, limit: i64) -> &mut Self;
-}
+    /// Add where lower than condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_lt("age", 18);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE age < $1");
+    /// ```
+    fn where_lt<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::lt(field, value))
+    }
 
-pub trait QueryBuilderWithOffset {
-    fn offset(&mut self, offset: i64) -> &mut Self;
-}
+    /// Add where lower than or equal condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_lte("age", 18);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE age <= $1");
+    /// ```
+    fn where_lte<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::lte(field, value))
+    }
 
-pub trait QueryBuilderWithJoin {
-    fn inner_join(&mut self, table_name: &str, relation: &str) -> &mut Self;
-    fn left_join(&mut self, table_name: &str, relation: &str) -> &mut Self;
-    fn left_outer_join(&mut self, table_name: &str, relation: &str) -> &mut Self;
-}
+    /// Add where greater than condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_gt("age", 18);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE age > $1");
+    /// ```
+    fn where_gt<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::gt(field, value))
+    }
 
-pub trait QueryBuilderWithSet {
-    fn set<T: 'static + ToSql + Sync + Clone>(&mut self, field: &str, value: T) -> &mut Self;
-    fn set_computed(&mut self, field: &str, value: &str) -> &mut Self;
-}
+    /// Add where greater than or equal condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_gte("age", 18);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE age >= $1");
+    /// ```
+    fn where_gte<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::gte(field, value))
+    }
 
-pub enum Order {
-    Asc(String),
-    Desc(String),
-}
+    /// Add where distinct condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_distinct("email", "rick@example.com".to_string());
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE email IS DISTINCT FROM $1");
+    /// ```
+    fn where_distinct<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::distinct(field, value))
+    }
 
-impl Order {
-    pub fn to_string(&self) -> String {
-        match self {
-            Order::Asc(column) => format!("{} ASC", column),
-            Order::Desc(column) => format!("{} DESC", column),
-        }
+    /// Add where not distinct condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_not_distinct("email", "rick@example.com".to_string());
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE email IS NOT DISTINCT FROM $1");
+    /// ```
+    fn where_not_distinct<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::not_distinct(field, value))
     }
-}
 
-pub trait QueryBuilderWithOrder {
-    fn order_by(&mut self, field: Order);
-}
+    /// Add where like condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_like("name", "ri%".to_string());
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE name LIKE $1");
+    /// ```
+    fn where_like<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::like(field, value))
+    }
 
-pub trait QueryBuilderWithQueries {
-    fn with_query(&mut self, name: &str, query: &str) -> &mut Self;
+    /// Add where case insensitive like condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_ilike("name", "ri%".to_string());
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE name ILIKE $1");
+    /// ```
+    fn where_ilike<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::ilike(field, value))
+    }
+
+    /// Add where POSIX regular expression match condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_regex("name", "^ri".to_string());
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE name ~ $1");
+    /// ```
+    fn where_regex<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::regex(field, value))
+    }
+
+    /// Add where case insensitive POSIX regular expression match condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_iregex("name", "^ri".to_string());
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE name ~* $1");
+    /// ```
+    fn where_iregex<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::iregex(field, value))
+    }
+
+    /// Add where between condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_between("age", 18, 28);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE age BETWEEN $1 AND $2");
+    /// ```
+    fn where_between<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        low: T,
+        high: T,
+    ) -> &mut Self {
+        self.where_cond(Condition::between(field, low, high))
+    }
+
+    /// Add where is null condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_is_null("deleted_at");
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE deleted_at IS NULL");
+    /// ```
+    fn where_is_null<F: Into<Expression>>(&mut self, field: F) -> &mut Self {
+        self.where_cond(Condition::is_null(field))
+    }
+
+    /// Add where is not null condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_is_not_null("deleted_at");
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE deleted_at IS NOT NULL");
+    /// ```
+    fn where_is_not_null<F: Into<Expression>>(&mut self, field: F) -> &mut Self {
+        self.where_cond(Condition::is_not_null(field))
+    }
+
+    /// Add where in condition to query, binding each value as a parameter
+    ///
+    /// An empty list results in an always false condition. The query has one
+    /// placeholder per value, use `where_eq_any` to bind the values as a
+    /// single array parameter and keep the same query for any number of values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_in("id", vec![1, 2, 3]);
+    /// builder.where_in::<_, i32>("age", vec![]);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE id IN ($1, $2, $3) AND false");
+    /// ```
+    fn where_in<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        values: Vec<T>,
+    ) -> &mut Self {
+        self.where_cond(Condition::is_in(field, values))
+    }
+
+    /// Add where not in condition to query, binding each value as a parameter
+    ///
+    /// An empty list results in an always true condition. The query has one
+    /// placeholder per value, use `where_ne_all` to bind the values as a
+    /// single array parameter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_not_in("id", vec![1, 2]);
+    /// builder.where_not_in::<_, i32>("age", vec![]);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE id NOT IN ($1, $2) AND true");
+    /// ```
+    fn where_not_in<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        values: Vec<T>,
+    ) -> &mut Self {
+        self.where_cond(Condition::is_not_in(field, values))
+    }
+
+    /// Add where equal any condition to query, binding the values as a single array parameter
+    ///
+    /// An empty list results in an always false condition. Unlike `where_in`,
+    /// the query doesn't depend on the number of values, so a prepared
+    /// statement can be reused, but the values must convert to a postgres array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_eq_any("id", vec![1, 2, 3]);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE id = ANY($1)");
+    /// ```
+    fn where_eq_any<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        values: Vec<T>,
+    ) -> &mut Self {
+        self.where_cond(Condition::eq_any(field, values))
+    }
+
+    /// Add where not equal all condition to query, binding the values as a single array parameter
+    ///
+    /// An empty list results in an always true condition. Unlike `where_not_in`,
+    /// the query doesn't depend on the number of values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_ne_all("id", vec![1, 2, 3]);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users WHERE id <> ALL($1)");
+    /// ```
+    fn where_ne_all<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        values: Vec<T>,
+    ) -> &mut Self {
+        self.where_cond(Condition::ne_all(field, values))
+    }
+
+    /// Add where in subquery condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut authors = SelectBuilder::new("articles");
+    /// authors.select("author_id");
+    /// authors.where_eq("published", true);
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_eq("active", true);
+    /// builder.where_in_select("id", authors);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users WHERE active = $1 AND id IN (SELECT author_id FROM articles WHERE published = $2)"
+    /// );
+    /// ```
+    fn where_in_select<F: Into<Expression>, Q: SelectQuery>(
+        &mut self,
+        field: F,
+        query: Q,
+    ) -> &mut Self {
+        self.where_cond(Condition::in_select(field, query))
+    }
+
+    /// Add where not in subquery condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut authors = SelectBuilder::new("articles");
+    /// authors.select("author_id");
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_not_in_select("id", authors);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users WHERE id NOT IN (SELECT author_id FROM articles)"
+    /// );
+    /// ```
+    fn where_not_in_select<F: Into<Expression>, Q: SelectQuery>(
+        &mut self,
+        field: F,
+        query: Q,
+    ) -> &mut Self {
+        self.where_cond(Condition::not_in_select(field, query))
+    }
+
+    /// Add where exists condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut articles = SelectBuilder::new("articles");
+    /// articles.where_condition("articles.author_id = users.id");
+    /// articles.where_eq("articles.published", true);
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_exists(articles);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users WHERE EXISTS (SELECT * FROM articles WHERE articles.author_id = users.id AND articles.published = $1)"
+    /// );
+    /// ```
+    fn where_exists<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.where_cond(Condition::exists(query))
+    }
+
+    /// Add where not exists condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut articles = SelectBuilder::new("articles");
+    /// articles.where_condition("articles.author_id = users.id");
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.where_not_exists(articles);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users WHERE NOT EXISTS (SELECT * FROM articles WHERE articles.author_id = users.id)"
+    /// );
+    /// ```
+    fn where_not_exists<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.where_cond(Condition::not_exists(query))
+    }
+}
+
+pub enum Grouping {
+    Column(String),
+    Rollup(Vec<String>),
+    Cube(Vec<String>),
+    Sets(Vec<Vec<String>>),
+}
+
+impl fmt::Display for Grouping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Grouping::Column(column) => write!(f, "{}", column),
+            Grouping::Rollup(columns) => write!(f, "ROLLUP ({})", columns.join(", ")),
+            Grouping::Cube(columns) => write!(f, "CUBE ({})", columns.join(", ")),
+            Grouping::Sets(sets) => {
+                let sets: Vec<String> = sets
+                    .iter()
+                    .map(|set| format!("({})", set.join(", ")))
+                    .collect();
+                write!(f, "GROUPING SETS ({})", sets.join(", "))
+            }
+        }
+    }
+}
+
+fn to_strings(columns: &[&str]) -> Vec<String> {
+    columns.iter().map(|column| column.to_string()).collect()
+}
+
+pub trait QueryBuilderWithGroupBy {
+    fn group_by<E: Into<Expression>>(&mut self, field: E) -> &mut Self;
+
+    /// Add a grouping element to query
+    ///
+    /// Grouping elements are made of column names, use `group_by` to group
+    /// by an expression carrying parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{Grouping, QueryBuilder, QueryBuilderWithGroupBy};
+    ///
+    /// let mut builder = SelectBuilder::new("sales");
+    /// builder.group_by_element(Grouping::Column("country".into()));
+    /// builder.group_by_element(Grouping::Cube(vec!["brand".into(), "size".into()]));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM sales GROUP BY country, CUBE (brand, size)"
+    /// );
+    /// ```
+    fn group_by_element(&mut self, element: Grouping) -> &mut Self {
+        self.group_by(element.to_string().as_str())
+    }
+
+    /// Add a `ROLLUP` grouping element to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithGroupBy};
+    ///
+    /// let mut builder = SelectBuilder::new("sales");
+    /// builder.group_by_rollup(&["brand", "size"]);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM sales GROUP BY ROLLUP (brand, size)");
+    /// ```
+    fn group_by_rollup(&mut self, columns: &[&str]) -> &mut Self {
+        self.group_by_element(Grouping::Rollup(to_strings(columns)))
+    }
+
+    /// Add a `CUBE` grouping element to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithGroupBy};
+    ///
+    /// let mut builder = SelectBuilder::new("sales");
+    /// builder.group_by_cube(&["brand", "size"]);
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM sales GROUP BY CUBE (brand, size)");
+    /// ```
+    fn group_by_cube(&mut self, columns: &[&str]) -> &mut Self {
+        self.group_by_element(Grouping::Cube(to_strings(columns)))
+    }
+
+    /// Add a `GROUPING SETS` grouping element to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithGroupBy};
+    ///
+    /// let mut builder = SelectBuilder::new("sales");
+    /// builder.group_by_sets(&[&["brand", "size"], &["brand"], &[]]);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM sales GROUP BY GROUPING SETS ((brand, size), (brand), ())"
+    /// );
+    /// ```
+    fn group_by_sets(&mut self, sets: &[&[&str]]) -> &mut Self {
+        let sets = sets.iter().map(|set| to_strings(set)).collect();
+        self.group_by_element(Grouping::Sets(sets))
+    }
+}
+
+/// Filter the groups of a query
+///
+/// The helpers mirror the ones of `QueryBuilderWithWhere`, building a
+/// `Condition` added with `having_cond`.
+pub trait QueryBuilderWithHaving: QueryBuilder {
+    /// Add having condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithGroupBy, QueryBuilderWithHaving};
+    ///
+    /// let mut builder = SelectBuilder::new("articles");
+    /// builder.select("author_id");
+    /// builder.group_by("author_id");
+    /// let threshold = builder.add_param(3i64);
+    /// let condition = format!("count(*) > ${}", threshold);
+    /// builder.having_condition(condition.as_str());
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT author_id FROM articles GROUP BY author_id HAVING count(*) > $1"
+    /// );
+    /// ```
+    fn having_condition(&mut self, raw: &str) -> &mut Self;
+
+    /// Add a composed having condition to query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Condition, SelectBuilder};
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithGroupBy, QueryBuilderWithHaving};
+    ///
+    /// let mut builder = SelectBuilder::new("articles");
+    /// builder.select("author_id");
+    /// builder.group_by("author_id");
+    /// builder.having_cond(Condition::gt("count(*)", 3i64).or(Condition::eq("author_id", 42)));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT author_id FROM articles GROUP BY author_id HAVING (count(*) > $1 OR author_id = $2)"
+    /// );
+    /// ```
+    fn having_cond(&mut self, condition: Condition) -> &mut Self {
+        let condition = condition
+            .into_operand()
+            .merge_with(|query, params| self.merge_params(query, params));
+        self.having_condition(condition.as_str());
+        self
+    }
+
+    /// Add having equal condition to query
+    fn having_eq<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::eq(field, value))
+    }
+
+    /// Add having not equal condition to query
+    fn having_ne<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::ne(field, value))
+    }
+
+    /// Add having lower than condition to query
+    fn having_lt<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::lt(field, value))
+    }
+
+    /// Add having lower than or equal condition to query
+    fn having_lte<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::lte(field, value))
+    }
+
+    /// Add having greater than condition to query
+    fn having_gt<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::gt(field, value))
+    }
+
+    /// Add having greater than or equal condition to query
+    fn having_gte<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::gte(field, value))
+    }
+
+    /// Add having between condition to query
+    fn having_between<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        low: T,
+        high: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::between(field, low, high))
+    }
+
+    /// Add having distinct condition to query
+    fn having_distinct<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::distinct(field, value))
+    }
+
+    /// Add having not distinct condition to query
+    fn having_not_distinct<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::not_distinct(field, value))
+    }
+
+    /// Add having like condition to query
+    fn having_like<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::like(field, value))
+    }
+
+    /// Add having case insensitive like condition to query
+    fn having_ilike<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::ilike(field, value))
+    }
+
+    /// Add having POSIX regular expression match condition to query
+    fn having_regex<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::regex(field, value))
+    }
+
+    /// Add having case insensitive POSIX regular expression match condition to query
+    fn having_iregex<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        value: T,
+    ) -> &mut Self {
+        self.having_cond(Condition::iregex(field, value))
+    }
+
+    /// Add having is null condition to query
+    fn having_is_null<F: Into<Expression>>(&mut self, field: F) -> &mut Self {
+        self.having_cond(Condition::is_null(field))
+    }
+
+    /// Add having is not null condition to query
+    fn having_is_not_null<F: Into<Expression>>(&mut self, field: F) -> &mut Self {
+        self.having_cond(Condition::is_not_null(field))
+    }
+
+    /// Add having in condition to query, binding each value as a parameter
+    ///
+    /// An empty list results in an always false condition.
+    fn having_in<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        values: Vec<T>,
+    ) -> &mut Self {
+        self.having_cond(Condition::is_in(field, values))
+    }
+
+    /// Add having not in condition to query, binding each value as a parameter
+    ///
+    /// An empty list results in an always true condition.
+    fn having_not_in<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        values: Vec<T>,
+    ) -> &mut Self {
+        self.having_cond(Condition::is_not_in(field, values))
+    }
+
+    /// Add having equal any condition to query, binding the values as a single array parameter
+    ///
+    /// An empty list results in an always false condition.
+    fn having_eq_any<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        values: Vec<T>,
+    ) -> &mut Self {
+        self.having_cond(Condition::eq_any(field, values))
+    }
+
+    /// Add having not equal all condition to query, binding the values as a single array parameter
+    ///
+    /// An empty list results in an always true condition.
+    fn having_ne_all<F: Into<Expression>, T: 'static + ToSql + Sync + Clone>(
+        &mut self,
+        field: F,
+        values: Vec<T>,
+    ) -> &mut Self {
+        self.having_cond(Condition::ne_all(field, values))
+    }
+
+    /// Add having in subquery condition to query
+    fn having_in_select<F: Into<Expression>, Q: SelectQuery>(
+        &mut self,
+        field: F,
+        query: Q,
+    ) -> &mut Self {
+        self.having_cond(Condition::in_select(field, query))
+    }
+
+    /// Add having not in subquery condition to query
+    fn having_not_in_select<F: Into<Expression>, Q: SelectQuery>(
+        &mut self,
+        field: F,
+        query: Q,
+    ) -> &mut Self {
+        self.having_cond(Condition::not_in_select(field, query))
+    }
+
+    /// Add having exists condition to query
+    fn having_exists<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.having_cond(Condition::exists(query))
+    }
+
+    /// Add having not exists condition to query
+    fn having_not_exists<Q: SelectQuery>(&mut self, query: Q) -> &mut Self {
+        self.having_cond(Condition::not_exists(query))
+    }
+}
+
+pub trait QueryBuilderWithLimit {
+    fn limit(&mut self, limit: i64) -> &mut Self;
+}
+
+pub trait QueryBuilderWithOffset {
+    fn offset(&mut self, offset: i64) -> &mut Self;
+}
+
+pub trait QueryBuilderWithJoin {
+    /// Add a join to the query, merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Condition, SelectBuilder};
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut builder = SelectBuilder::new("users u");
+    /// builder.where_eq("u.active", true);
+    /// builder.join(
+    ///     Join::left("articles")
+    ///         .alias("a")
+    ///         .on("a.author_id = u.id")
+    ///         .on_condition(Condition::eq("a.published", true)),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users u LEFT JOIN articles AS a ON a.author_id = u.id AND a.published = $2 WHERE u.active = $1"
+    /// );
+    /// ```
+    fn join(&mut self, join: Join) -> &mut Self;
+
+    /// Add an inner join to the query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithJoin;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.inner_join("articles", "articles.author_id = users.id");
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM users INNER JOIN articles ON articles.author_id = users.id"
+    /// );
+    /// ```
+    fn inner_join<E: Into<Expression>>(&mut self, table_name: E, relation: &str) -> &mut Self {
+        self.join(Join::inner(table_name).on(relation))
+    }
+
+    fn left_join<E: Into<Expression>>(&mut self, table_name: E, relation: &str) -> &mut Self {
+        self.join(Join::left(table_name).on(relation))
+    }
+
+    fn left_outer_join<E: Into<Expression>>(&mut self, table_name: E, relation: &str) -> &mut Self {
+        self.join(Join::left_outer(table_name).on(relation))
+    }
+
+    fn right_join<E: Into<Expression>>(&mut self, table_name: E, relation: &str) -> &mut Self {
+        self.join(Join::right(table_name).on(relation))
+    }
+
+    fn full_outer_join<E: Into<Expression>>(&mut self, table_name: E, relation: &str) -> &mut Self {
+        self.join(Join::full_outer(table_name).on(relation))
+    }
+
+    /// Add a lateral left join against a select query, keeping every row
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut top = SelectBuilder::new("scores");
+    /// top.select("points");
+    /// top.where_condition("scores.player_id = players.id");
+    /// top.where_gt("points", 10);
+    /// top.limit(3);
+    /// let mut builder = SelectBuilder::new("players");
+    /// builder.select("players.name");
+    /// builder.select("top.points");
+    /// builder.left_join_lateral(top, "top");
+    /// builder.where_eq("players.team", "blue".to_string());
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT players.name, top.points FROM players LEFT JOIN LATERAL (SELECT points FROM scores WHERE scores.player_id = players.id AND points > $1 LIMIT $2) AS top ON true WHERE players.team = $3"
+    /// );
+    /// ```
+    fn left_join_lateral<Q: SelectQuery>(&mut self, query: Q, alias: &str) -> &mut Self {
+        self.join(
+            Join::select(JoinKind::Left, query, alias)
+                .lateral()
+                .on("true"),
+        )
+    }
+
+    /// Add a lateral cross join against a select query
+    fn cross_join_lateral<Q: SelectQuery>(&mut self, query: Q, alias: &str) -> &mut Self {
+        self.join(Join::select(JoinKind::Cross, query, alias).lateral())
+    }
+
+    /// Add a cross join to the query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithJoin};
+    ///
+    /// let mut builder = SelectBuilder::new("sizes");
+    /// builder.cross_join("colors");
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT * FROM sizes CROSS JOIN colors");
+    /// ```
+    fn cross_join<E: Into<Expression>>(&mut self, table_name: E) -> &mut Self {
+        self.join(Join::cross(table_name))
+    }
+
+    /// Add a natural inner join to the query
+    fn natural_join<E: Into<Expression>>(&mut self, table_name: E) -> &mut Self {
+        self.join(Join::inner(table_name).natural())
+    }
+
+    /// Add an inner join on columns having the same name in both tables
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithJoin};
+    ///
+    /// let mut builder = SelectBuilder::new("orders");
+    /// builder.inner_join_using("customers", &["customer_id", "region"]);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM orders INNER JOIN customers USING (customer_id, region)"
+    /// );
+    /// ```
+    fn inner_join_using<E: Into<Expression>>(
+        &mut self,
+        table_name: E,
+        columns: &[&str],
+    ) -> &mut Self {
+        self.join(Join::inner(table_name).using(columns))
+    }
+}
+
+pub trait QueryBuilderWithReturning {
+    /// Add a column to return from the affected rows
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::UpdateBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithReturning, QueryBuilderWithSet};
+    ///
+    /// let mut builder = UpdateBuilder::new("users");
+    /// builder.set("name", "rick".to_string());
+    /// builder.returning("id");
+    /// builder.returning("name");
+    ///
+    /// assert_eq!(builder.get_query(), "UPDATE users SET name = $1 RETURNING id, name");
+    /// ```
+    fn returning<E: Into<Expression>>(&mut self, column: E) -> &mut Self;
+}
+
+pub trait QueryBuilderWithSet {
+    fn set<T: 'static + ToSql + Sync + Clone>(&mut self, field: &str, value: T) -> &mut Self;
+    fn set_computed<E: Into<Expression>>(&mut self, field: &str, value: E) -> &mut Self;
+}
+
+pub enum Order {
+    Asc(String),
+    Desc(String),
+    Using(String, String),
+}
+
+impl Order {
+    /// Get the ordered expression
+    pub fn expression(&self) -> &str {
+        match self {
+            Order::Asc(column) => column.as_str(),
+            Order::Desc(column) => column.as_str(),
+            Order::Using(column, _) => column.as_str(),
+        }
+    }
+
+    fn direction(&self) -> String {
+        match self {
+            Order::Asc(_) => "ASC".into(),
+            Order::Desc(_) => "DESC".into(),
+            Order::Using(_, operator) => format!("USING {}", operator),
+        }
+    }
+
+    fn with_expression(&self, expression: String) -> Order {
+        match self {
+            Order::Asc(_) => Order::Asc(expression),
+            Order::Desc(_) => Order::Desc(expression),
+            Order::Using(_, operator) => Order::Using(expression, operator.clone()),
+        }
+    }
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.expression(), self.direction())
+    }
+}
+
+pub enum Nulls {
+    First,
+    Last,
+}
+
+impl fmt::Display for Nulls {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Nulls::First => write!(f, "NULLS FIRST"),
+            Nulls::Last => write!(f, "NULLS LAST"),
+        }
+    }
+}
+
+/// An order with its collation, nulls placement and parameters
+///
+/// The placeholders of the ordered expression are numbered from `$1`
+/// against the bound parameters and are shifted when added to a builder.
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::prelude::{Nulls, Order, Ordering};
+///
+/// let ordering = Ordering::new(Order::Desc("name".into()))
+///     .collate("C")
+///     .nulls(Nulls::Last);
+///
+/// assert_eq!(ordering.to_string(), "name COLLATE \"C\" DESC NULLS LAST");
+/// ```
+pub struct Ordering {
+    order: Order,
+    collation: Option<String>,
+    nulls: Option<Nulls>,
+    fragment: Fragment,
+}
+
+impl Ordering {
+    pub fn new(order: Order) -> Self {
+        let fragment = Fragment::raw(order.expression());
+        Ordering {
+            order,
+            collation: None,
+            nulls: None,
+            fragment,
+        }
+    }
+
+    /// Create an ascending order on an expression, keeping its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Expression, SelectBuilder};
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut builder = SelectBuilder::new("shops");
+    /// builder.where_eq("open", true);
+    /// builder.order_by(Ordering::asc(
+    ///     Expression::column("location").operate("<->", Expression::param(vec![2.35, 48.85])),
+    /// ));
+    /// builder.order_by(Ordering::desc("rating"));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM shops WHERE open = $1 ORDER BY location <-> $2 ASC, rating DESC"
+    /// );
+    /// ```
+    pub fn asc<E: Into<Expression>>(expression: E) -> Self {
+        Ordering::from_expression(expression.into(), Order::Asc)
+    }
+
+    /// Create a descending order on an expression, keeping its parameters
+    pub fn desc<E: Into<Expression>>(expression: E) -> Self {
+        Ordering::from_expression(expression.into(), Order::Desc)
+    }
+
+    fn from_expression(expression: Expression, order: fn(String) -> Order) -> Self {
+        let fragment = expression.into_fragment();
+        let mut ordering = Ordering::new(order(fragment.to_string()));
+        ordering.fragment = fragment;
+        ordering
+    }
+
+    /// Set the collation of the ordered expression, quoting its name
+    pub fn collate(mut self, collation: &str) -> Self {
+        self.collation = Some(collation.into());
+        self
+    }
+
+    /// Set where the null values are placed
+    pub fn nulls(mut self, nulls: Nulls) -> Self {
+        self.nulls = Some(nulls);
+        self
+    }
+
+    /// Bind a parameter to the ordered expression
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut builder = SelectBuilder::new("shops");
+    /// builder.where_eq("open", true);
+    /// builder.order_by(Ordering::new(Order::Asc("location <-> point($1, $2)".into())).bind(2.35).bind(48.85));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM shops WHERE open = $1 ORDER BY location <-> point($2, $3) ASC"
+    /// );
+    /// ```
+    pub fn bind<T: 'static + ToSql + Sync + Clone>(mut self, value: T) -> Self {
+        self.fragment.bind(value);
+        self
+    }
+
+    /// Get the ordered expression
+    pub fn expression(&self) -> &str {
+        self.order.expression()
+    }
+
+    /// Move the parameters of the ordering into a bucket
+    pub(crate) fn merge_into(self, params: &mut Bucket) -> Self {
+        let expression = self.fragment.merge_into(params);
+        Ordering {
+            fragment: Fragment::raw(expression.as_str()),
+            order: self.order.with_expression(expression),
+            ..self
+        }
+    }
+
+    fn suffix(&self) -> String {
+        let mut result = String::new();
+        if let Some(collation) = self.collation.as_ref() {
+            result = format!(" COLLATE \"{}\"", collation.replace('"', "\"\""));
+        }
+        result = format!("{} {}", result, self.order.direction());
+        if let Some(nulls) = self.nulls.as_ref() {
+            result = format!("{} {}", result, nulls);
+        }
+        result
+    }
+
+    /// Render the ordering, numbering its parameters after `offset` ones
+    pub(crate) fn render(&self, offset: &mut usize) -> String {
+        format!("{}{}", self.fragment.render(offset), self.suffix())
+    }
+
+    pub(crate) fn into_fragment(self) -> Fragment {
+        let suffix = self.suffix();
+        let mut fragment = self.fragment;
+        fragment.push_str(suffix.as_str());
+        fragment
+    }
+}
+
+impl From<Order> for Ordering {
+    fn from(order: Order) -> Self {
+        Ordering::new(order)
+    }
+}
+
+impl fmt::Display for Ordering {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(&mut 0))
+    }
+}
+
+pub trait QueryBuilderWithOrder {
+    fn order_by<O: Into<Ordering>>(&mut self, field: O) -> &mut Self;
+}
+
+pub enum LockStrength {
+    Update,
+    NoKeyUpdate,
+    Share,
+    KeyShare,
+}
+
+impl fmt::Display for LockStrength {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LockStrength::Update => write!(f, "FOR UPDATE"),
+            LockStrength::NoKeyUpdate => write!(f, "FOR NO KEY UPDATE"),
+            LockStrength::Share => write!(f, "FOR SHARE"),
+            LockStrength::KeyShare => write!(f, "FOR KEY SHARE"),
+        }
+    }
+}
+
+pub enum LockWait {
+    NoWait,
+    SkipLocked,
+}
+
+impl fmt::Display for LockWait {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LockWait::NoWait => write!(f, "NOWAIT"),
+            LockWait::SkipLocked => write!(f, "SKIP LOCKED"),
+        }
+    }
+}
+
+/// A row locking clause with its locked tables and waiting policy
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::prelude::Lock;
+///
+/// let lock = Lock::update().of("jobs").skip_locked();
+///
+/// assert_eq!(lock.to_string(), "FOR UPDATE OF jobs SKIP LOCKED");
+/// ```
+pub struct Lock {
+    strength: LockStrength,
+    tables: Vec<String>,
+    wait: Option<LockWait>,
+}
+
+impl Lock {
+    pub fn new(strength: LockStrength) -> Self {
+        Lock {
+            strength,
+            tables: vec![],
+            wait: None,
+        }
+    }
+
+    /// Lock the selected rows against any concurrent modification
+    pub fn update() -> Self {
+        Lock::new(LockStrength::Update)
+    }
+
+    /// Lock the selected rows against modifications, except the ones
+    /// leaving their keys untouched
+    pub fn no_key_update() -> Self {
+        Lock::new(LockStrength::NoKeyUpdate)
+    }
+
+    /// Lock the selected rows against modifications, allowing other
+    /// shared locks
+    pub fn share() -> Self {
+        Lock::new(LockStrength::Share)
+    }
+
+    /// Lock the selected rows against deletions and key modifications
+    pub fn key_share() -> Self {
+        Lock::new(LockStrength::KeyShare)
+    }
+
+    /// Restrict the lock to the rows of a table
+    pub fn of(mut self, table: &str) -> Self {
+        self.tables.push(table.into());
+        self
+    }
+
+    /// Fail instead of waiting for rows locked by other transactions
+    pub fn nowait(mut self) -> Self {
+        self.wait = Some(LockWait::NoWait);
+        self
+    }
+
+    /// Skip the rows locked by other transactions instead of waiting
+    pub fn skip_locked(mut self) -> Self {
+        self.wait = Some(LockWait::SkipLocked);
+        self
+    }
+}
+
+impl fmt::Display for Lock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.strength)?;
+        if !self.tables.is_empty() {
+            write!(f, " OF {}", self.tables.join(", "))?;
+        }
+        if let Some(wait) = self.wait.as_ref() {
+            write!(f, " {}", wait)?;
+        }
+        Ok(())
+    }
+}
+
+pub trait QueryBuilderWithQueries {
+    /// Add a query to the `WITH` clause, merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{UpdateBuilder, WithQuery};
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut builder = UpdateBuilder::new("users");
+    /// builder.with(WithQuery::new("banned", "SELECT id FROM bans").not_materialized());
+    /// builder.set("active", false);
+    /// builder.where_condition("id IN (SELECT id FROM banned)");
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "WITH banned AS NOT MATERIALIZED (SELECT id FROM bans) UPDATE users SET active = $1 WHERE id IN (SELECT id FROM banned)"
+    /// );
+    /// ```
+    fn with(&mut self, query: WithQuery) -> &mut Self;
+
+    fn with_query(&mut self, name: &str, query: &str) -> &mut Self {
+        self.with(WithQuery::new(name, query))
+    }
+
+    /// Add a select query as a `WITH` query, merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithQueries;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut adults = SelectBuilder::new("users");
+    /// adults.where_gte("age", 18);
+    /// let mut builder = SelectBuilder::new("adults");
+    /// builder.where_eq("name", "rick".to_string());
+    /// builder.with_select("adults", adults);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "WITH adults AS (SELECT * FROM users WHERE age >= $2) SELECT * FROM adults WHERE name = $1"
+    /// );
+    /// ```
+    fn with_select<Q: SelectQuery>(&mut self, name: &str, query: Q) -> &mut Self {
+        self.with(WithQuery::select(name, query))
+    }
+
+    /// Add an insert, update or delete query as a `WITH` query, merging its
+    /// parameters
+    ///
+    /// The rows listed in its `RETURNING` clause can be used by the main query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{DeleteBuilder, InsertBuilder, SelectBuilder};
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut moved = DeleteBuilder::new("users");
+    /// moved.where_eq("active", false);
+    /// moved.returning("*");
+    /// let mut builder = InsertBuilder::new("archived_users");
+    /// builder.with_modifying("moved", moved);
+    /// builder.select(&[], SelectBuilder::new("moved"));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "WITH moved AS (DELETE FROM users WHERE active = $1 RETURNING *) INSERT INTO archived_users SELECT * FROM moved"
+    /// );
+    /// ```
+    fn with_modifying<Q: ModifyingQuery>(&mut self, name: &str, query: Q) -> &mut Self {
+        self.with(WithQuery::modifying(name, query))
+    }
+
+    /// Add a recursive `WITH` query made of a non recursive select query
+    /// and a recursive one, each wrapped in parentheses and combined with
+    /// `UNION ALL`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut base = SelectBuilder::new("employees");
+    /// base.select("id");
+    /// base.select("1");
+    /// base.where_eq("id", 42);
+    /// let mut step = SelectBuilder::new("employees");
+    /// step.select("employees.id");
+    /// step.select("depth + 1");
+    /// step.inner_join("team", "employees.manager_id = team.id");
+    /// let mut builder = SelectBuilder::new("team");
+    /// builder.with_recursive_select("team", &["id", "depth"], base, step);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "WITH RECURSIVE team(id, depth) AS ((SELECT id, 1 FROM employees WHERE id = $1) UNION ALL (SELECT employees.id, depth + 1 FROM employees INNER JOIN team ON employees.manager_id = team.id)) SELECT * FROM team"
+    /// );
+    /// ```
+    fn with_recursive_select<B: SelectQuery, S: SelectQuery>(
+        &mut self,
+        name: &str,
+        columns: &[&str],
+        base: B,
+        step: S,
+    ) -> &mut Self {
+        self.with(WithQuery::recursive_select(name, base, step).columns(columns))
+    }
 }
@@ -1,17 +1,26 @@
 use crate::bucket::Bucket;
+use crate::error::Error;
+use crate::expression::{quote_identifier, Expression};
 use crate::prelude::*;
+use crate::window::Window;
+use crate::with_query::{with_clause, WithQuery};
 use postgres_types::ToSql;
 
 pub struct SelectBuilder {
-    with_queries: Vec<(String, String)>,
+    with_queries: Vec<WithQuery>,
+    distinct: bool,
+    distinct_on: Vec<String>,
     columns: Vec<String>,
     from_table: String,
     conditions: Vec<String>,
     joins: Vec<Join>,
     groups: Vec<String>,
-    order: Vec<Order>,
+    havings: Vec<String>,
+    windows: Vec<String>,
+    order: Vec<Ordering>,
     limit: Option<String>,
     offset: Option<String>,
+    locks: Vec<Lock>,
     params: Bucket,
 }
 
@@ -28,37 +37,281 @@ impl SelectBuilder {
     pub fn new(from: &str) -> Self {
         SelectBuilder {
             with_queries: vec![],
+            distinct: false,
+            distinct_on: vec![],
             columns: vec![],
             from_table: from.into(),
             conditions: vec![],
             joins: vec![],
             groups: vec![],
+            havings: vec![],
+            windows: vec![],
             order: vec![],
             limit: None,
             offset: None,
+            locks: vec![],
             params: Bucket::new(),
         }
     }
 
-    /// Add a column to select
+    /// Create a new select query from a subquery
     ///
     /// # Examples
     ///
     /// ```
     /// use postgres_querybuilder::SelectBuilder;
     /// use postgres_querybuilder::prelude::QueryBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilderWithWhere;
+    ///
+    /// let mut adults = SelectBuilder::new("users");
+    /// adults.where_gte("age", 18);
+    /// let mut builder = SelectBuilder::from_subquery(adults, "adults");
+    /// builder.where_eq("adults.name", "rick".to_string());
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT * FROM (SELECT * FROM users WHERE age >= $1) AS adults WHERE adults.name = $2"
+    /// );
+    /// ```
+    pub fn from_subquery<Q: SelectQuery>(query: Q, alias: &str) -> Self {
+        let from = format!("({}) AS {}", query.get_query(), alias);
+        let mut builder = SelectBuilder::new(from.as_str());
+        builder.params = query.get_params();
+        builder
+    }
+
+    /// Add a column or an expression to select, merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Expression, SelectBuilder};
+    /// use postgres_querybuilder::prelude::QueryBuilder;
     ///
     /// let mut builder = SelectBuilder::new("users");
     /// builder.select("id");
     /// builder.select("email");
+    /// builder.select(Expression::column("age").gte(Expression::param(18)));
     ///
-    /// assert_eq!(builder.get_query(), "SELECT id, email FROM users");
+    /// assert_eq!(builder.get_query(), "SELECT id, email, age >= $1 FROM users");
     /// ```
-    pub fn select(&mut self, column: &str) -> &mut Self {
-        self.columns.push(column.to_string());
+    pub fn select<E: Into<Expression>>(&mut self, column: E) -> &mut Self {
+        let column = column.into().merge_into(&mut self.params);
+        self.columns.push(column);
         self
     }
 
+    /// Add an expression to select under an alias, binding its parameters to
+    /// the query
+    ///
+    /// The alias is quoted when it is not a plain lowercase identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Expression, SelectBuilder};
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.select_as(Expression::param("admin".to_string()).cast("text"), "tag");
+    /// builder.select_as("first_name || ' ' || last_name", "Full Name");
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT CAST($1 AS text) AS tag, first_name || ' ' || last_name AS \"Full Name\" FROM users"
+    /// );
+    /// ```
+    pub fn select_as<E: Into<Expression>>(&mut self, expression: E, alias: &str) -> &mut Self {
+        let column = expression.into().merge_into(&mut self.params);
+        self.columns
+            .push(format!("{} AS {}", column, quote_identifier(alias)));
+        self
+    }
+
+    /// Only return distinct rows
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::QueryBuilder;
+    ///
+    /// let mut builder = SelectBuilder::new("users");
+    /// builder.select("name");
+    /// builder.distinct();
+    ///
+    /// assert_eq!(builder.get_query(), "SELECT DISTINCT name FROM users");
+    /// ```
+    pub fn distinct(&mut self) -> &mut Self {
+        self.distinct = true;
+        self
+    }
+
+    /// Only return the first row of each set of rows where the given expressions are equal
+    ///
+    /// The expressions must match the leftmost order expressions, which is
+    /// not checked by `get_query` but by [`validate`](#method.validate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{Order, QueryBuilder, QueryBuilderWithOrder};
+    ///
+    /// let mut builder = SelectBuilder::new("articles");
+    /// builder.distinct_on("author_id");
+    /// builder.order_by(Order::Asc("author_id".into()));
+    /// builder.order_by(Order::Desc("created_at".into()));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT DISTINCT ON (author_id) * FROM articles ORDER BY author_id ASC, created_at DESC"
+    /// );
+    /// assert!(builder.validate().is_ok());
+    /// ```
+    pub fn distinct_on<E: Into<Expression>>(&mut self, expression: E) -> &mut Self {
+        let expression = expression.into().merge_into(&mut self.params);
+        self.distinct_on.push(expression);
+        self
+    }
+
+    /// Add a named window to the `WINDOW` clause, merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{Aggregate, Expression, SelectBuilder, Window, WindowFunction};
+    /// use postgres_querybuilder::prelude::{Order, QueryBuilder, QueryBuilderWithWhere};
+    ///
+    /// let mut builder = SelectBuilder::new("scores");
+    /// builder.where_eq("season", 3);
+    /// builder.select("player_id");
+    /// builder.select(WindowFunction::rank().over_named("w"));
+    /// builder.select(Aggregate::avg(Expression::column("points")).over_named("w"));
+    /// builder.window(
+    ///     "w",
+    ///     Window::new()
+    ///         .partition_by(Expression::column("points").gt(Expression::param(10)))
+    ///         .order_by(Order::Desc("points".into())),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT player_id, rank() OVER w, avg(points) OVER w FROM scores WHERE season = $1 WINDOW w AS (PARTITION BY points > $2 ORDER BY points DESC)"
+    /// );
+    /// ```
+    pub fn window(&mut self, name: &str, window: Window) -> &mut Self {
+        let specification = window.merge_into(&mut self.params);
+        self.windows
+            .push(format!("{} AS ({})", name, specification));
+        self
+    }
+
+    /// Lock the selected rows
+    ///
+    /// Locking is not allowed with `DISTINCT`, `GROUP BY` or `HAVING`, which is
+    /// not checked by `get_query` but by [`validate`](#method.validate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{Lock, QueryBuilder, QueryBuilderWithLimit, QueryBuilderWithWhere};
+    ///
+    /// let mut builder = SelectBuilder::new("jobs");
+    /// builder.select("id");
+    /// builder.where_eq("status", "pending".to_string());
+    /// builder.limit(1);
+    /// builder.lock(Lock::update().skip_locked());
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT id FROM jobs WHERE status = $1 LIMIT $2 FOR UPDATE SKIP LOCKED"
+    /// );
+    /// ```
+    pub fn lock(&mut self, lock: Lock) -> &mut Self {
+        self.locks.push(lock);
+        self
+    }
+
+    /// Check that the query is accepted by Postgres
+    ///
+    /// The query returned by `get_query` is not checked, this must be called
+    /// before running a query built from dynamic input. Each join is checked
+    /// with [`Join::validate`](prelude/struct.Join.html#method.validate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::error::Error;
+    /// use postgres_querybuilder::prelude::{Order, QueryBuilderWithOrder};
+    ///
+    /// let mut builder = SelectBuilder::new("articles");
+    /// builder.distinct_on("author_id");
+    /// builder.order_by(Order::Desc("created_at".into()));
+    ///
+    /// assert_eq!(builder.validate(), Err(Error::DistinctOnOrderMismatch));
+    /// ```
+    pub fn validate(&self) -> Result<(), Error> {
+        if !self.distinct_on.is_empty() {
+            // the orders may only go past the DISTINCT ON expressions once
+            // they all have been ordered
+            let leading = self
+                .order
+                .iter()
+                .take_while(|order| {
+                    self.distinct_on
+                        .iter()
+                        .any(|item| item == order.expression())
+                })
+                .count();
+            let covered = self.distinct_on.iter().all(|item| {
+                self.order[..leading]
+                    .iter()
+                    .any(|order| item == order.expression())
+            });
+            if leading < self.order.len() && !covered {
+                return Err(Error::DistinctOnOrderMismatch);
+            }
+        }
+        let grouped = self.distinct
+            || !self.distinct_on.is_empty()
+            || !self.groups.is_empty()
+            || !self.havings.is_empty();
+        if !self.locks.is_empty() && grouped {
+            return Err(Error::LockingNotAllowed);
+        }
+        for join in self.joins.iter() {
+            join.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Add a `GROUPING` function call to select
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::SelectBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithGroupBy};
+    ///
+    /// let mut builder = SelectBuilder::new("sales");
+    /// builder.select("brand");
+    /// builder.select("size");
+    /// builder.select_grouping(&["brand", "size"]);
+    /// builder.group_by_rollup(&["brand", "size"]);
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "SELECT brand, size, GROUPING(brand, size) FROM sales GROUP BY ROLLUP (brand, size)"
+    /// );
+    /// ```
+    pub fn select_grouping(&mut self, columns: &[&str]) -> &mut Self {
+        let column = format!("GROUPING({})", columns.join(", "));
+        self.select(column.as_str())
+    }
+
     /// Add a raw where condition
     ///
     /// # Examples
@@ -80,33 +333,43 @@ impl SelectBuilder {
 
 impl SelectBuilder {
     fn with_queries_to_query(&self) -> Option<String> {
-        if self.with_queries.len() > 0 {
-            let result: Vec<String> = self
-                .with_queries
-                .iter()
-                .map(|item| format!("{} AS ({})", item.0, item.1))
-                .collect();
-            Some(format!("WITH {}", result.join(", ")))
-        } else {
-            None
-        }
+        with_clause(&self.with_queries)
     }
 
     fn select_to_query(&self) -> String {
-        let columns = if self.columns.len() == 0 {
+        let columns = if self.columns.is_empty() {
             "*".to_string()
         } else {
             self.columns.join(", ")
         };
-        format!("SELECT {}", columns)
+        if !self.distinct_on.is_empty() {
+            format!(
+                "SELECT DISTINCT ON ({}) {}",
+                self.distinct_on.join(", "),
+                columns
+            )
+        } else if self.distinct {
+            format!("SELECT DISTINCT {}", columns)
+        } else {
+            format!("SELECT {}", columns)
+        }
     }
 
-    fn from_to_query(&self) -> String {
+    fn table_to_query(&self) -> String {
         format!("FROM {}", self.from_table)
     }
 
+    fn joins_to_query(&self) -> Option<String> {
+        if !self.joins.is_empty() {
+            let result: Vec<String> = self.joins.iter().map(|join| join.to_string()).collect();
+            Some(result.join(" "))
+        } else {
+            None
+        }
+    }
+
     fn where_to_query(&self) -> Option<String> {
-        if self.conditions.len() > 0 {
+        if !self.conditions.is_empty() {
             let result = self.conditions.join(" AND ");
             Some(format!("WHERE {}", result))
         } else {
@@ -115,7 +378,7 @@ impl SelectBuilder {
     }
 
     fn group_by_to_query(&self) -> Option<String> {
-        if self.groups.len() > 0 {
+        if !self.groups.is_empty() {
             let result = self.groups.join(", ");
             Some(format!("GROUP BY {}", result))
         } else {
@@ -123,8 +386,25 @@ impl SelectBuilder {
         }
     }
 
+    fn having_to_query(&self) -> Option<String> {
+        if !self.havings.is_empty() {
+            let result = self.havings.join(" AND ");
+            Some(format!("HAVING {}", result))
+        } else {
+            None
+        }
+    }
+
+    fn windows_to_query(&self) -> Option<String> {
+        if !self.windows.is_empty() {
+            Some(format!("WINDOW {}", self.windows.join(", ")))
+        } else {
+            None
+        }
+    }
+
     fn order_by_to_query(&self) -> Option<String> {
-        if self.order.len() > 0 {
+        if !self.order.is_empty() {
             let result: Vec<String> = self.order.iter().map(|order| order.to_string()).collect();
             Some(format!("ORDER BY {}", result.join(", ")))
         } else {
@@ -133,16 +413,21 @@ impl SelectBuilder {
     }
 
     fn limit_to_query(&self) -> Option<String> {
-        match self.limit.as_ref() {
-            Some(limit) => Some(format!("LIMIT {}", limit)),
-            None => None,
-        }
+        self.limit.as_ref().map(|limit| format!("LIMIT {}", limit))
     }
 
     fn offset_to_query(&self) -> Option<String> {
-        match self.offset.as_ref() {
-            Some(offset) => Some(format!("OFFSET {}", offset)),
-            None => None,
+        self.offset
+            .as_ref()
+            .map(|offset| format!("OFFSET {}", offset))
+    }
+
+    fn locks_to_query(&self) -> Option<String> {
+        if !self.locks.is_empty() {
+            let result: Vec<String> = self.locks.iter().map(|lock| lock.to_string()).collect();
+            Some(result.join(" "))
+        } else {
+            None
         }
     }
 }
@@ -152,42 +437,58 @@ impl QueryBuilder for SelectBuilder {
         self.params.push(value)
     }
 
+    fn merge_params(&mut self, query: &str, params: Bucket) -> String {
+        self.params.merge(query, params)
+    }
+
     fn get_query(&self) -> String {
         let mut sections: Vec<String> = vec![];
-        match self.with_queries_to_query() {
-            Some(value) => sections.push(value),
-            None => (),
-        };
+        if let Some(value) = self.with_queries_to_query() {
+            sections.push(value);
+        }
         sections.push(self.select_to_query());
-        sections.push(self.from_to_query());
-        match self.where_to_query() {
-            Some(value) => sections.push(value),
-            None => (),
-        };
-        match self.group_by_to_query() {
-            Some(value) => sections.push(value),
-            None => (),
-        };
-        match self.order_by_to_query() {
-            Some(value) => sections.push(value),
-            None => (),
-        };
-        match self.limit_to_query() {
-            Some(value) => sections.push(value),
-            None => (),
-        };
-        match self.offset_to_query() {
-            Some(value) => sections.push(value),
-            None => (),
-        };
+        sections.push(self.table_to_query());
+        if let Some(value) = self.joins_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.where_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.group_by_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.having_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.windows_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.order_by_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.limit_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.offset_to_query() {
+            sections.push(value);
+        }
+        if let Some(value) = self.locks_to_query() {
+            sections.push(value);
+        }
         sections.join(" ")
     }
 
-    fn get_ref_params(self) -> Vec<&'static (dyn ToSql + Sync)> {
+    fn get_params(self) -> Bucket {
+        self.params
+    }
+
+    fn get_ref_params(&self) -> Vec<&(dyn ToSql + Sync)> {
         self.params.get_refs()
     }
 }
 
+impl SelectQuery for SelectBuilder {}
+
 impl QueryBuilderWithWhere for SelectBuilder {
     fn where_condition(&mut self, raw: &str) -> &mut Self {
         self.conditions.push(raw.to_string());
@@ -212,30 +513,24 @@ impl QueryBuilderWithOffset for SelectBuilder {
 }
 
 impl QueryBuilderWithJoin for SelectBuilder {
-    fn inner_join(&mut self, table_name: &str, relation: &str) -> &mut Self {
-        self.joins
-            .push(Join::Inner(table_name.to_string(), relation.to_string()));
-        self
-    }
-
-    fn left_join(&mut self, table_name: &str, relation: &str) -> &mut Self {
-        self.joins.push(Join::LeftOuter(
-            table_name.to_string(),
-            relation.to_string(),
-        ));
+    fn join(&mut self, join: Join) -> &mut Self {
+        let join = join.merge_into(&mut self.params);
+        self.joins.push(join);
         self
     }
+}
 
-    fn left_outer_join(&mut self, table_name: &str, relation: &str) -> &mut Self {
-        self.joins
-            .push(Join::Left(table_name.to_string(), relation.to_string()));
+impl QueryBuilderWithGroupBy for SelectBuilder {
+    fn group_by<E: Into<Expression>>(&mut self, field: E) -> &mut Self {
+        let field = field.into().merge_into(&mut self.params);
+        self.groups.push(field);
         self
     }
 }
 
-impl QueryBuilderWithGroupBy for SelectBuilder {
-    fn group_by(&mut self, field: &str) -> &mut Self {
-        self.groups.push(field.to_string());
+impl QueryBuilderWithHaving for SelectBuilder {
+    fn having_condition(&mut self, raw: &str) -> &mut Self {
+        self.havings.push(raw.to_string());
         self
     }
 }
@@ -252,18 +547,23 @@ impl QueryBuilderWithOrder for SelectBuilder {
     /// use postgres_querybuilder::prelude::QueryBuilderWithOrder;
     ///
     /// let mut builder = SelectBuilder::new("users");
-    /// builder.order_by(Order::Asc("name".into()));
+    /// builder
+    ///     .order_by(Order::Asc("name".into()))
+    ///     .order_by(Order::Using("age".into(), ">".into()));
     ///
-    /// assert_eq!(builder.get_query(), "SELECT * FROM users ORDER BY name ASC");
+    /// assert_eq!(builder.get_query(), "SELECT * FROM users ORDER BY name ASC, age USING >");
     /// ```
-    fn order_by(&mut self, field: Order) {
-        self.order.push(field);
+    fn order_by<O: Into<Ordering>>(&mut self, field: O) -> &mut Self {
+        let ordering = field.into().merge_into(&mut self.params);
+        self.order.push(ordering);
+        self
     }
 }
 
 impl QueryBuilderWithQueries for SelectBuilder {
-    fn with_query(&mut self, name: &str, query: &str) -> &mut Self {
-        self.with_queries.push((name.into(), query.into()));
+    fn with(&mut self, query: WithQuery) -> &mut Self {
+        let query = query.merge_into(&mut self.params);
+        self.with_queries.push(query);
         self
     }
 }
@@ -271,6 +571,9 @@ impl QueryBuilderWithQueries for SelectBuilder {
 #[cfg(test)]
 pub mod test {
     use super::*;
+    use crate::aggregate::Aggregate;
+    use crate::condition::Condition;
+    use crate::window::WindowFunction;
 
     #[test]
     fn from_scratch() {
@@ -320,6 +623,350 @@ pub mod test {
         );
     }
 
+    #[test]
+    fn with_joins() {
+        let mut builder = SelectBuilder::new("publishers");
+        builder.select("publishers.id");
+        builder.inner_join("articles", "articles.publisher_id = publishers.id");
+        builder.left_join("authors", "authors.id = articles.author_id");
+        builder.left_outer_join("reviews", "reviews.article_id = articles.id");
+        builder.where_eq("authors.id", 42);
+        assert_eq!(
+            builder.get_query(),
+            "SELECT publishers.id FROM publishers INNER JOIN articles ON articles.publisher_id = publishers.id LEFT JOIN authors ON authors.id = articles.author_id LEFT OUTER JOIN reviews ON reviews.article_id = articles.id WHERE authors.id = $1"
+        );
+    }
+
+    #[test]
+    fn with_join_vocabulary() {
+        let mut builder = SelectBuilder::new("publishers p");
+        builder.right_join("articles", "articles.publisher_id = p.id");
+        builder.full_outer_join("authors", "authors.id = articles.author_id");
+        builder.cross_join("languages");
+        builder.natural_join("countries");
+        builder.join(Join::left("reviews").alias("r").using(&["article_id"]));
+        builder.join(Join::full("editors").natural());
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM publishers p RIGHT JOIN articles ON articles.publisher_id = p.id FULL OUTER JOIN authors ON authors.id = articles.author_id CROSS JOIN languages NATURAL INNER JOIN countries LEFT JOIN reviews AS r USING (article_id) NATURAL FULL JOIN editors"
+        );
+    }
+
+    #[test]
+    fn with_join_params() {
+        let mut builder = SelectBuilder::new("publishers");
+        builder.where_eq("publishers.active", true);
+        builder.join(
+            Join::inner("articles")
+                .on("articles.publisher_id = publishers.id")
+                .on_condition(Condition::gt("articles.views", 10))
+                .on_condition(Condition::lt("articles.views", 100)),
+        );
+        builder.where_eq("articles.lang", "en".to_string());
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM publishers INNER JOIN articles ON articles.publisher_id = publishers.id AND articles.views > $2 AND articles.views < $3 WHERE publishers.active = $1 AND articles.lang = $4"
+        );
+        assert_eq!(builder.get_params().len(), 4);
+    }
+
+    #[test]
+    fn with_raw_join_keeping_placeholders() {
+        let mut builder = SelectBuilder::new("publishers");
+        let index = builder.add_param(5);
+        builder.inner_join("articles", format!("articles.views > ${}", index).as_str());
+        builder.cross_join(format!("generate_series(1, ${})", index).as_str());
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM publishers INNER JOIN articles ON articles.views > $1 CROSS JOIN generate_series(1, $1)"
+        );
+        assert!(builder.validate().is_ok());
+    }
+
+    #[test]
+    fn with_raw_window_keeping_placeholders() {
+        let mut builder = SelectBuilder::new("scores");
+        let index = builder.add_param(10);
+        builder.select(WindowFunction::rank().over_named("w"));
+        builder.window(
+            "w",
+            Window::new().partition_by(format!("points > ${}", index).as_str()),
+        );
+        assert_eq!(
+            builder.get_query(),
+            "SELECT rank() OVER w FROM scores WINDOW w AS (PARTITION BY points > $1)"
+        );
+    }
+
+    #[test]
+    fn with_invalid_joins() {
+        let mut builder = SelectBuilder::new("publishers");
+        builder.join(Join::left("articles"));
+        assert_eq!(builder.validate(), Err(Error::JoinConstraintRequired));
+        let mut builder = SelectBuilder::new("publishers");
+        builder.join(Join::cross("articles").using(&["id"]));
+        assert_eq!(builder.validate(), Err(Error::JoinConstraintNotAllowed));
+    }
+
+    #[test]
+    fn with_join_bound_after_conditions() {
+        let mut builder = SelectBuilder::new("publishers");
+        builder.where_eq("publishers.active", true);
+        builder.join(
+            Join::inner("generate_series(1, $1)")
+                .alias("n")
+                .on_condition(Condition::gt("n", 2))
+                .bind(10)
+                .on("n <> 5"),
+        );
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM publishers INNER JOIN generate_series(1, $2) AS n ON n > $3 AND n <> 5 WHERE publishers.active = $1"
+        );
+        assert_eq!(builder.get_params().len(), 3);
+    }
+
+    #[test]
+    fn with_lateral_joins() {
+        let mut latest = SelectBuilder::new("articles");
+        latest.where_condition("articles.publisher_id = publishers.id");
+        latest.where_eq("articles.lang", "en".to_string());
+        latest.limit(3);
+        let mut builder = SelectBuilder::new("publishers");
+        builder.where_eq("publishers.active", true);
+        builder.join(
+            Join::select(JoinKind::Inner, latest, "latest")
+                .lateral()
+                .on_condition(Condition::gt("latest.views", 10)),
+        );
+        builder.join(
+            Join::left("unnest(publishers.tags, $1::text[])")
+                .bind(vec!["a".to_string()])
+                .alias("t(tag, extra)")
+                .lateral()
+                .on("t.tag IS NOT NULL"),
+        );
+        builder.where_eq("publishers.country", "fr".to_string());
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM publishers INNER JOIN LATERAL (SELECT * FROM articles WHERE articles.publisher_id = publishers.id AND articles.lang = $2 LIMIT $3) AS latest ON latest.views > $4 LEFT JOIN LATERAL unnest(publishers.tags, $5::text[]) AS t(tag, extra) ON t.tag IS NOT NULL WHERE publishers.active = $1 AND publishers.country = $6"
+        );
+        assert_eq!(builder.get_params().len(), 6);
+    }
+
+    #[test]
+    fn with_expressions() {
+        let mut builder = SelectBuilder::new("publishers");
+        builder.where_eq("active", true);
+        builder.select("id");
+        builder.select(Expression::function(
+            "concat",
+            vec![
+                Expression::column("name"),
+                Expression::param("!".to_string()),
+            ],
+        ));
+        builder.distinct_on(Expression::column("country").cast("text"));
+        builder.group_by(Expression::function(
+            "date_trunc",
+            vec![
+                Expression::param("day".to_string()),
+                Expression::column("created_at"),
+            ],
+        ));
+        builder.order_by(Ordering::asc(Expression::column("country").cast("text")));
+        builder.order_by(Ordering::desc(
+            Expression::column("score") * Expression::param(2),
+        ));
+        assert_eq!(
+            builder.get_query(),
+            "SELECT DISTINCT ON (CAST(country AS text)) id, concat(name, $2) FROM publishers WHERE active = $1 GROUP BY date_trunc($3, created_at) ORDER BY CAST(country AS text) ASC, score * $4 DESC"
+        );
+        assert!(builder.validate().is_ok());
+        assert_eq!(builder.get_params().len(), 4);
+    }
+
+    #[test]
+    fn with_aliases() {
+        let mut builder = SelectBuilder::new("publishers");
+        builder.where_eq("active", true);
+        builder.select_as("id", "publisher_id");
+        builder.select_as(
+            Expression::function(
+                "coalesce",
+                vec![
+                    Expression::column("name"),
+                    Expression::param("?".to_string()),
+                ],
+            ),
+            "name",
+        );
+        builder.select_as(Expression::param(1) + Expression::param(2), "Sum \"1\"");
+        assert_eq!(
+            builder.get_query(),
+            "SELECT id AS publisher_id, coalesce(name, $2) AS name, $3 + $4 AS \"Sum \"\"1\"\"\" FROM publishers WHERE active = $1"
+        );
+        assert_eq!(builder.get_params().len(), 4);
+    }
+
+    #[test]
+    fn with_where_comparisons() {
+        let mut builder = SelectBuilder::new("publishers");
+        builder.where_gt("a", 1);
+        builder.where_gte("b", 2);
+        builder.where_lt("c", 3);
+        builder.where_lte("d", 4);
+        builder.where_between("e", 5, 6);
+        builder.where_is_null("f");
+        builder.where_is_not_null("g");
+        builder.where_distinct("h", 7);
+        builder.where_not_distinct("i", 8);
+        builder.where_like("j", "a%");
+        builder.where_ilike("k", "b%");
+        builder.where_regex("l", "^c");
+        builder.where_iregex("m", "^d");
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM publishers WHERE a > $1 AND b >= $2 AND c < $3 AND d <= $4 AND e BETWEEN $5 AND $6 AND f IS NULL AND g IS NOT NULL AND h IS DISTINCT FROM $7 AND i IS NOT DISTINCT FROM $8 AND j LIKE $9 AND k ILIKE $10 AND l ~ $11 AND m ~* $12"
+        );
+    }
+
+    #[test]
+    fn with_where_lists() {
+        let mut builder = SelectBuilder::new("publishers");
+        builder.where_in("a", vec![1, 2]);
+        builder.where_not_in("b", vec![3]);
+        builder.where_eq_any("c", vec![4, 5]);
+        builder.where_ne_all("d", vec![6, 7]);
+        builder.where_in::<_, i32>("e", vec![]);
+        builder.where_not_in::<_, i32>("f", vec![]);
+        builder.where_eq_any::<_, i32>("g", vec![]);
+        builder.where_ne_all::<_, i32>("h", vec![]);
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM publishers WHERE a IN ($1, $2) AND b NOT IN ($3) AND c = ANY($4) AND d <> ALL($5) AND false AND true AND false AND true"
+        );
+    }
+
+    #[test]
+    fn with_grouping_elements() {
+        let mut builder = SelectBuilder::new("sales");
+        builder.select("country");
+        builder.select_grouping(&["brand"]);
+        builder.group_by("country");
+        builder.group_by_rollup(&["brand", "size"]);
+        builder.group_by_cube(&["color"]);
+        builder.group_by_sets(&[&["shop", "city"], &[]]);
+        assert_eq!(
+            builder.get_query(),
+            "SELECT country, GROUPING(brand) FROM sales GROUP BY country, ROLLUP (brand, size), CUBE (color), GROUPING SETS ((shop, city), ())"
+        );
+    }
+
+    #[test]
+    fn with_distinct() {
+        let mut builder = SelectBuilder::new("publishers");
+        builder.select("name");
+        builder.distinct();
+        assert_eq!(builder.get_query(), "SELECT DISTINCT name FROM publishers");
+        assert!(builder.validate().is_ok());
+    }
+
+    #[test]
+    fn with_distinct_on() {
+        let mut builder = SelectBuilder::new("articles");
+        builder.distinct_on("author_id");
+        builder.distinct_on("publisher_id");
+        assert!(builder.validate().is_ok());
+        builder.order_by(Order::Asc("publisher_id".into()));
+        assert!(builder.validate().is_ok());
+        builder.order_by(Order::Desc("created_at".into()));
+        assert_eq!(builder.validate(), Err(Error::DistinctOnOrderMismatch));
+        let mut builder = SelectBuilder::new("articles");
+        builder.distinct_on("author_id");
+        builder.distinct_on("publisher_id");
+        builder.order_by(Order::Asc("publisher_id".into()));
+        builder.order_by(Order::Desc("author_id".into()));
+        builder.order_by(Order::Desc("created_at".into()));
+        assert!(builder.validate().is_ok());
+        assert_eq!(
+            builder.get_query(),
+            "SELECT DISTINCT ON (author_id, publisher_id) * FROM articles ORDER BY publisher_id ASC, author_id DESC, created_at DESC"
+        );
+    }
+
+    #[test]
+    fn with_distinct_on_mismatch() {
+        let mut builder = SelectBuilder::new("articles");
+        builder.distinct_on("author_id");
+        builder.order_by(Order::Desc("created_at".into()));
+        builder.order_by(Order::Asc("author_id".into()));
+        assert_eq!(builder.validate(), Err(Error::DistinctOnOrderMismatch));
+    }
+
+    #[test]
+    fn with_having() {
+        let mut builder = SelectBuilder::new("articles");
+        builder.select("author_id");
+        builder.select("count(*)");
+        builder.where_eq("published", true);
+        builder.group_by("author_id");
+        builder.having_gt("count(*)", 2i64);
+        builder.having_lte("max(likes)", 100);
+        builder.having_condition("min(likes) > 0");
+        builder.limit(5);
+        assert_eq!(
+            builder.get_query(),
+            "SELECT author_id, count(*) FROM articles WHERE published = $1 GROUP BY author_id HAVING count(*) > $2 AND max(likes) <= $3 AND min(likes) > 0 LIMIT $4"
+        );
+    }
+
+    #[test]
+    fn with_expression_fields() {
+        let mut builder = SelectBuilder::new("users");
+        builder.join(
+            Join::cross(Expression::function(
+                "generate_series",
+                vec![Expression::literal(1), Expression::param(3)],
+            ))
+            .alias("n"),
+        );
+        builder.where_eq(
+            Expression::function("lower", vec![Expression::column("email")]),
+            "rick@example.com".to_string(),
+        );
+        builder.where_in(
+            Expression::column("age") + Expression::param(1),
+            vec![18, 21],
+        );
+        builder.group_by("n");
+        builder.having_gt(Aggregate::count_all(), 2i64);
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM users CROSS JOIN generate_series(1, $1) AS n WHERE lower(email) = $2 AND (age + $3) IN ($4, $5) GROUP BY n HAVING count(*) > $6"
+        );
+        assert_eq!(builder.get_params().len(), 6);
+    }
+
+    #[test]
+    fn with_having_helpers() {
+        let mut bans = SelectBuilder::new("bans");
+        bans.where_condition("bans.user_id = articles.author_id");
+        bans.where_gt("bans.created_at", "2020-01-01");
+        let mut builder = SelectBuilder::new("articles");
+        builder.select("author_id");
+        builder.where_eq("published", true);
+        builder.group_by("author_id");
+        builder.having_is_not_null("max(title)");
+        builder.having_in("count(*)", vec![1i64, 2i64]);
+        builder.having_ilike("max(title)", "%pickle%");
+        builder.having_not_exists(bans);
+        assert_eq!(
+            builder.get_query(),
+            "SELECT author_id FROM articles WHERE published = $1 GROUP BY author_id HAVING max(title) IS NOT NULL AND count(*) IN ($2, $3) AND max(title) ILIKE $4 AND NOT EXISTS (SELECT * FROM bans WHERE bans.user_id = articles.author_id AND bans.created_at > $5)"
+        );
+    }
+
     #[test]
     fn with_order() {
         let mut builder = SelectBuilder::new("publishers");
@@ -332,6 +979,80 @@ pub mod test {
         );
     }
 
+    #[test]
+    fn with_raw_order_keeping_placeholders() {
+        let mut builder = SelectBuilder::new("publishers");
+        let index = builder.add_param(5);
+        builder.order_by(Order::Asc(format!("abs(id - ${})", index)));
+        builder.order_by(Ordering::desc("name").collate("en\"US"));
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM publishers ORDER BY abs(id - $1) ASC, name COLLATE \"en\"\"US\" DESC"
+        );
+    }
+
+    #[test]
+    fn with_order_mixing_raw_strings_and_params() {
+        let mut builder = SelectBuilder::new("publishers");
+        let index = builder.add_param(5);
+        builder.order_by(Ordering::asc(Expression::function(
+            "greatest",
+            vec![
+                Expression::raw(format!("id - ${}", index).as_str()),
+                Expression::param(10),
+            ],
+        )));
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM publishers ORDER BY greatest(id - $1, $2) ASC"
+        );
+    }
+
+    #[test]
+    fn with_nested_selects() {
+        let mut counts = SelectBuilder::new("articles");
+        counts.select("author_id");
+        counts.select("count(*) AS total");
+        counts.where_eq("published", true);
+        counts.group_by("author_id");
+        let mut banned = SelectBuilder::new("banned");
+        banned.select("user_id");
+        banned.where_gt("until", 10);
+        let mut source = SelectBuilder::new("users");
+        source.where_eq("active", true);
+        let mut builder = SelectBuilder::from_subquery(source, "active_users");
+        builder.where_eq("active_users.name", "rick");
+        builder.with_select("counts", counts);
+        builder.where_not_in_select("active_users.id", banned);
+        builder.limit(10);
+        assert_eq!(
+            builder.get_query(),
+            "WITH counts AS (SELECT author_id, count(*) AS total FROM articles WHERE published = $3 GROUP BY author_id) SELECT * FROM (SELECT * FROM users WHERE active = $1) AS active_users WHERE active_users.name = $2 AND active_users.id NOT IN (SELECT user_id FROM banned WHERE until > $4) LIMIT $5"
+        );
+        assert_eq!(builder.get_params().len(), 5);
+    }
+
+    #[test]
+    fn with_rich_order() {
+        let mut builder = SelectBuilder::new("shops");
+        builder.where_eq("open", true);
+        builder
+            .order_by(Ordering::new(Order::Asc("name".into())).collate("C"))
+            .order_by(Ordering::new(Order::Desc("rating".into())).nulls(Nulls::Last))
+            .order_by(
+                Ordering::new(Order::Asc("location <-> point($1, $2)".into()))
+                    .bind(2.35)
+                    .bind(48.85)
+                    .nulls(Nulls::First),
+            )
+            .order_by(Order::Using("id".into(), "<".into()));
+        builder.limit(10);
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM shops WHERE open = $1 ORDER BY name COLLATE \"C\" ASC, rating DESC NULLS LAST, location <-> point($2, $3) ASC NULLS FIRST, id USING < LIMIT $4"
+        );
+    }
+
     #[test]
     fn with_subquery() {
         let mut builder = SelectBuilder::new("publishers_view");
@@ -345,4 +1066,32 @@ pub mod test {
       "WITH publishers_count AS (SELECT publisher_id, count(*) FROM articles GROUP BY publisher_id), publishers_subquery AS (SELECT * FROM publishers) SELECT * FROM publishers_view"
     );
     }
+
+    #[test]
+    fn with_locks() {
+        let mut builder = SelectBuilder::new("jobs");
+        builder.inner_join("queues", "queues.id = jobs.queue_id");
+        builder.limit(10);
+        builder.offset(5);
+        builder.lock(Lock::no_key_update().of("jobs").nowait());
+        builder.lock(Lock::key_share().of("queues"));
+        assert_eq!(
+            builder.get_query(),
+            "SELECT * FROM jobs INNER JOIN queues ON queues.id = jobs.queue_id LIMIT $1 OFFSET $2 FOR NO KEY UPDATE OF jobs NOWAIT FOR KEY SHARE OF queues"
+        );
+        assert!(builder.validate().is_ok());
+    }
+
+    #[test]
+    fn with_lock_and_group_by() {
+        let mut builder = SelectBuilder::new("jobs");
+        builder.select("queue_id");
+        builder.group_by("queue_id");
+        builder.lock(Lock::share());
+        assert_eq!(
+            builder.get_query(),
+            "SELECT queue_id FROM jobs GROUP BY queue_id FOR SHARE"
+        );
+        assert_eq!(builder.validate(), Err(Error::LockingNotAllowed));
+    }
 }
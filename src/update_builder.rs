@@ -1,12 +1,16 @@
 use crate::bucket::Bucket;
+use crate::expression::Expression;
 use crate::prelude::*;
+use crate::with_query::{with_clause, WithQuery};
 use postgres_types::ToSql;
 
 pub struct UpdateBuilder {
-    with_queries: Vec<(String, String)>,
+    with_queries: Vec<WithQuery>,
     table: String,
     fields: Vec<String>,
+    from: Vec<String>,
     conditions: Vec<String>,
+    returning: Vec<String>,
     params: Bucket,
 }
 
@@ -31,32 +35,76 @@ impl UpdateBuilder {
             with_queries: vec![],
             table: from.into(),
             fields: vec![],
+            from: vec![],
             conditions: vec![],
+            returning: vec![],
             params: Bucket::new(),
         }
     }
+
+    /// Add a table to the from clause
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::UpdateBuilder;
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithSet, QueryBuilderWithWhere};
+    ///
+    /// let mut builder = UpdateBuilder::new("users");
+    /// builder.set_computed("name", "staging.name");
+    /// builder.from("staging_users staging");
+    /// builder.where_condition("users.id = staging.id");
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "UPDATE users SET name = staging.name FROM staging_users staging WHERE users.id = staging.id"
+    /// );
+    /// ```
+    pub fn from(&mut self, table: &str) -> &mut Self {
+        self.from.push(table.into());
+        self
+    }
+
+    /// Add a subquery to the from clause, merging its parameters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{SelectBuilder, UpdateBuilder};
+    /// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithSet, QueryBuilderWithWhere};
+    ///
+    /// let mut staging = SelectBuilder::new("staging_users");
+    /// staging.where_eq("batch", 3);
+    /// let mut builder = UpdateBuilder::new("users");
+    /// builder.set("updated", true);
+    /// builder.set_computed("name", "staging.name");
+    /// builder.from_select(staging, "staging");
+    /// builder.where_condition("users.id = staging.id");
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "UPDATE users SET updated = $1, name = staging.name FROM (SELECT * FROM staging_users WHERE batch = $2) AS staging WHERE users.id = staging.id"
+    /// );
+    /// ```
+    pub fn from_select<Q: SelectQuery>(&mut self, query: Q, alias: &str) -> &mut Self {
+        let subquery = query.get_query();
+        let subquery = self.params.merge(subquery.as_str(), query.get_params());
+        self.from.push(format!("({}) AS {}", subquery, alias));
+        self
+    }
 }
 
 impl UpdateBuilder {
     fn with_queries_to_query(&self) -> Option<String> {
-        if self.with_queries.len() > 0 {
-            let result: Vec<String> = self
-                .with_queries
-                .iter()
-                .map(|item| format!("{} AS ({})", item.0, item.1))
-                .collect();
-            Some(format!("WITH {}", result.join(", ")))
-        } else {
-            None
-        }
+        with_clause(&self.with_queries)
     }
 
-    fn from_to_query(&self) -> String {
+    fn table_to_query(&self) -> String {
         format!("UPDATE {}", self.table)
     }
 
     fn set_to_query(&self) -> Option<String> {
-        if self.fields.len() > 0 {
+        if !self.fields.is_empty() {
             let fields_query = self.fields.join(", ");
             Some(format!("SET {}", fields_query))
         } else {
@@ -64,14 +112,30 @@ impl UpdateBuilder {
         }
     }
 
+    fn sources_to_query(&self) -> Option<String> {
+        if !self.from.is_empty() {
+            Some(format!("FROM {}", self.from.join(", ")))
+        } else {
+            None
+        }
+    }
+
     fn where_to_query(&self) -> Option<String> {
-        if self.conditions.len() > 0 {
+        if !self.conditions.is_empty() {
             let where_query = self.conditions.join(" AND ");
             Some(format!("WHERE {}", where_query))
         } else {
             None
         }
     }
+
+    fn returning_to_query(&self) -> Option<String> {
+        if !self.returning.is_empty() {
+            Some(format!("RETURNING {}", self.returning.join(", ")))
+        } else {
+            None
+        }
+    }
 }
 
 impl QueryBuilder for UpdateBuilder {
@@ -79,25 +143,36 @@ impl QueryBuilder for UpdateBuilder {
         self.params.push(value)
     }
 
+    fn merge_params(&mut self, query: &str, params: Bucket) -> String {
+        self.params.merge(query, params)
+    }
+
     fn get_query(&self) -> String {
         let mut result: Vec<String> = vec![];
-        match self.with_queries_to_query() {
-            Some(value) => result.push(value),
-            None => (),
-        };
-        result.push(self.from_to_query());
-        match self.set_to_query() {
-            Some(value) => result.push(value),
-            None => (),
-        };
-        match self.where_to_query() {
-            Some(value) => result.push(value),
-            None => (),
-        };
+        if let Some(value) = self.with_queries_to_query() {
+            result.push(value);
+        }
+        result.push(self.table_to_query());
+        if let Some(value) = self.set_to_query() {
+            result.push(value);
+        }
+        if let Some(value) = self.sources_to_query() {
+            result.push(value);
+        }
+        if let Some(value) = self.where_to_query() {
+            result.push(value);
+        }
+        if let Some(value) = self.returning_to_query() {
+            result.push(value);
+        }
         result.join(" ")
     }
 
-    fn get_ref_params(self) -> Vec<&'static (dyn ToSql + Sync)> {
+    fn get_params(self) -> Bucket {
+        self.params
+    }
+
+    fn get_ref_params(&self) -> Vec<&(dyn ToSql + Sync)> {
         self.params.get_refs()
     }
 }
@@ -109,6 +184,14 @@ impl QueryBuilderWithWhere for UpdateBuilder {
     }
 }
 
+impl QueryBuilderWithReturning for UpdateBuilder {
+    fn returning<E: Into<Expression>>(&mut self, column: E) -> &mut Self {
+        let column = column.into().merge_into(&mut self.params);
+        self.returning.push(column);
+        self
+    }
+}
+
 impl QueryBuilderWithSet for UpdateBuilder {
     fn set<T: 'static + ToSql + Sync + Clone>(&mut self, field: &str, value: T) -> &mut Self {
         let index = self.params.push(value);
@@ -116,15 +199,19 @@ impl QueryBuilderWithSet for UpdateBuilder {
         self
     }
 
-    fn set_computed(&mut self, field: &str, value: &str) -> &mut Self {
+    fn set_computed<E: Into<Expression>>(&mut self, field: &str, value: E) -> &mut Self {
+        let value = value.into().merge_into(&mut self.params);
         self.fields.push(format!("{} = {}", field, value));
         self
     }
 }
 
+impl ModifyingQuery for UpdateBuilder {}
+
 impl QueryBuilderWithQueries for UpdateBuilder {
-    fn with_query(&mut self, name: &str, query: &str) -> &mut Self {
-        self.with_queries.push((name.into(), query.into()));
+    fn with(&mut self, query: WithQuery) -> &mut Self {
+        let query = query.merge_into(&mut self.params);
+        self.with_queries.push(query);
         self
     }
 }
@@ -132,6 +219,8 @@ impl QueryBuilderWithQueries for UpdateBuilder {
 #[cfg(test)]
 pub mod test {
     use super::*;
+    use crate::insert_builder::InsertBuilder;
+    use crate::select_builder::SelectBuilder;
 
     #[test]
     fn from_scratch() {
@@ -161,4 +250,69 @@ pub mod test {
             "UPDATE publishers SET id = $2, trololo = md5(42) WHERE trololo = $1"
         );
     }
+
+    #[test]
+    fn with_returning() {
+        let mut builder = UpdateBuilder::new("publishers");
+        builder.set("name", "rick");
+        builder.where_eq("id", 42);
+        builder.returning("id");
+        builder.returning("name");
+        assert_eq!(
+            builder.get_query(),
+            "UPDATE publishers SET name = $1 WHERE id = $2 RETURNING id, name"
+        );
+    }
+
+    #[test]
+    fn with_from() {
+        let mut staging = SelectBuilder::new("staging");
+        staging.where_eq("batch", 3);
+        let mut builder = UpdateBuilder::new("publishers");
+        builder.set("active", true);
+        builder.set_computed("name", "s.name");
+        builder.from("other_publishers o");
+        builder.from_select(staging, "s");
+        builder.where_condition("publishers.id = s.id AND o.id = s.id");
+        builder.where_eq("o.active", false);
+        assert_eq!(
+            builder.get_query(),
+            "UPDATE publishers SET active = $1, name = s.name FROM other_publishers o, (SELECT * FROM staging WHERE batch = $2) AS s WHERE publishers.id = s.id AND o.id = s.id AND o.active = $3"
+        );
+    }
+
+    #[test]
+    fn with_modifying_query() {
+        let mut archived = InsertBuilder::new("archives");
+        archived.value("publisher_id", 42);
+        archived.returning("publisher_id");
+        let mut builder = UpdateBuilder::new("publishers");
+        builder.set("archived", true);
+        builder.with_modifying("archived", archived);
+        builder.where_condition("id IN (SELECT publisher_id FROM archived)");
+        builder.where_eq("active", false);
+        assert_eq!(
+            builder.get_query(),
+            "WITH archived AS (INSERT INTO archives (publisher_id) VALUES ($2) RETURNING publisher_id) UPDATE publishers SET archived = $1 WHERE id IN (SELECT publisher_id FROM archived) AND active = $3"
+        );
+        assert_eq!(builder.get_params().len(), 3);
+    }
+
+    #[test]
+    fn with_expressions() {
+        let mut builder = UpdateBuilder::new("publishers");
+        builder.set("active", true);
+        builder.set_computed("score", Expression::column("score") + Expression::param(1));
+        builder.set_computed("name", "upper(name)");
+        builder.where_eq("id", 42);
+        builder.returning(
+            Expression::column("score")
+                .cast("text")
+                .concat(Expression::param("pts".to_string())),
+        );
+        assert_eq!(
+            builder.get_query(),
+            "UPDATE publishers SET active = $1, score = score + $2, name = upper(name) WHERE id = $3 RETURNING CAST(score AS text) || $4"
+        );
+    }
 }
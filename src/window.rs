@@ -0,0 +1,313 @@
+use crate::bucket::Bucket;
+use crate::expression::Expression;
+use crate::fragment::Fragment;
+use crate::prelude::Ordering;
+use std::fmt;
+
+/// A bound of the frame of a window, in rows, values or groups of peers
+/// depending on the frame unit
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(u64),
+    CurrentRow,
+    Following(u64),
+    UnboundedFollowing,
+}
+
+impl fmt::Display for FrameBound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrameBound::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+            FrameBound::Preceding(offset) => write!(f, "{} PRECEDING", offset),
+            FrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            FrameBound::Following(offset) => write!(f, "{} FOLLOWING", offset),
+            FrameBound::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+/// A window specification carrying its own parameters
+///
+/// The placeholders of the partition and order expressions are numbered
+/// from `$1` against the window parameters and are shifted when the window
+/// is added to a builder, while raw strings keep their placeholders.
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::{FrameBound, Window};
+/// use postgres_querybuilder::prelude::Order;
+///
+/// let window = Window::new()
+///     .partition_by("author_id")
+///     .order_by(Order::Asc("created_at".into()))
+///     .rows(FrameBound::Preceding(2), FrameBound::CurrentRow);
+///
+/// assert_eq!(
+///     window.to_string(),
+///     "PARTITION BY author_id ORDER BY created_at ASC ROWS BETWEEN 2 PRECEDING AND CURRENT ROW"
+/// );
+/// ```
+pub struct Window {
+    base: Option<String>,
+    partitions: Vec<Fragment>,
+    order: Vec<Ordering>,
+    frame: Option<String>,
+}
+
+impl Window {
+    /// Create an empty window, spanning all the rows of the query
+    pub fn new() -> Self {
+        Window {
+            base: None,
+            partitions: vec![],
+            order: vec![],
+            frame: None,
+        }
+    }
+
+    /// Create a window extending a window of the `WINDOW` clause
+    pub fn extend(name: &str) -> Self {
+        let mut window = Window::new();
+        window.base = Some(name.into());
+        window
+    }
+
+    /// Add an expression to partition the rows by
+    pub fn partition_by<E: Into<Expression>>(mut self, expression: E) -> Self {
+        self.partitions.push(expression.into().into_fragment());
+        self
+    }
+
+    /// Add an order to the rows of a partition
+    pub fn order_by<O: Into<Ordering>>(mut self, field: O) -> Self {
+        self.order.push(field.into());
+        self
+    }
+
+    fn frame(mut self, unit: &str, start: FrameBound, end: FrameBound) -> Self {
+        self.frame = Some(format!("{} BETWEEN {} AND {}", unit, start, end));
+        self
+    }
+
+    /// Set the frame of the window in rows
+    pub fn rows(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame("ROWS", start, end)
+    }
+
+    /// Set the frame of the window in values of the order expression
+    pub fn range(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame("RANGE", start, end)
+    }
+
+    /// Set the frame of the window in groups of peer rows
+    pub fn groups(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame("GROUPS", start, end)
+    }
+
+    pub(crate) fn into_fragment(self) -> Fragment {
+        let mut sections: Vec<Fragment> = vec![];
+        if let Some(base) = self.base.as_ref() {
+            sections.push(Fragment::raw(base));
+        }
+        if !self.partitions.is_empty() {
+            let partitions = Fragment::join(self.partitions, ", ");
+            sections.push(partitions.wrap("PARTITION BY ", ""));
+        }
+        if !self.order.is_empty() {
+            let order = self
+                .order
+                .into_iter()
+                .map(Ordering::into_fragment)
+                .collect();
+            sections.push(Fragment::join(order, ", ").wrap("ORDER BY ", ""));
+        }
+        if let Some(frame) = self.frame.as_ref() {
+            sections.push(Fragment::raw(frame));
+        }
+        Fragment::join(sections, " ")
+    }
+
+    /// Move the parameters of the window into a bucket, returning its specification
+    pub(crate) fn merge_into(self, params: &mut Bucket) -> String {
+        self.into_fragment().merge_into(params)
+    }
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Window::new()
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sections: Vec<String> = vec![];
+        if let Some(base) = self.base.as_ref() {
+            sections.push(base.clone());
+        }
+        let mut offset = 0;
+        if !self.partitions.is_empty() {
+            let result: Vec<String> = self
+                .partitions
+                .iter()
+                .map(|partition| partition.render(&mut offset))
+                .collect();
+            sections.push(format!("PARTITION BY {}", result.join(", ")));
+        }
+        if !self.order.is_empty() {
+            let result: Vec<String> = self
+                .order
+                .iter()
+                .map(|order| order.render(&mut offset))
+                .collect();
+            sections.push(format!("ORDER BY {}", result.join(", ")));
+        }
+        if let Some(frame) = self.frame.as_ref() {
+            sections.push(frame.clone());
+        }
+        write!(f, "{}", sections.join(" "))
+    }
+}
+
+/// Build the `OVER` clause of a function call
+pub(crate) fn over(mut call: Fragment, window: Window) -> Expression {
+    call.push(window.into_fragment().wrap(" OVER (", ")"));
+    Expression::atom(call)
+}
+
+/// Build the `OVER` clause of a function call referring to a named window
+pub(crate) fn over_named(mut call: Fragment, name: &str) -> Expression {
+    call.push_str(format!(" OVER {}", name).as_str());
+    Expression::atom(call)
+}
+
+/// A function computed over a window of rows
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::{Expression, SelectBuilder, Window, WindowFunction};
+/// use postgres_querybuilder::prelude::{Order, QueryBuilder};
+///
+/// let mut builder = SelectBuilder::new("prices");
+/// builder.select("day");
+/// builder.select(WindowFunction::lag(Expression::column("price"), 1).over_named("w"));
+/// builder.select(WindowFunction::row_number().over(Window::extend("w")));
+/// builder.window("w", Window::new().order_by(Order::Asc("day".into())));
+///
+/// assert_eq!(
+///     builder.get_query(),
+///     "SELECT day, lag(price, 1) OVER w, row_number() OVER (w) FROM prices WINDOW w AS (ORDER BY day ASC)"
+/// );
+/// ```
+pub struct WindowFunction {
+    call: Expression,
+}
+
+impl WindowFunction {
+    /// Create a call to a window function, merging the parameters of its arguments
+    pub fn new(name: &str, arguments: Vec<Expression>) -> Self {
+        WindowFunction {
+            call: Expression::function(name, arguments),
+        }
+    }
+
+    /// Number the rows of the partition from 1
+    pub fn row_number() -> Self {
+        WindowFunction::new("row_number", vec![])
+    }
+
+    /// Rank the rows of the partition, with gaps
+    pub fn rank() -> Self {
+        WindowFunction::new("rank", vec![])
+    }
+
+    /// Rank the rows of the partition, without gaps
+    pub fn dense_rank() -> Self {
+        WindowFunction::new("dense_rank", vec![])
+    }
+
+    /// Get the value of an expression for the row `offset` rows before
+    pub fn lag(expression: Expression, offset: i64) -> Self {
+        WindowFunction::new("lag", vec![expression, Expression::literal(offset)])
+    }
+
+    /// Get the value of an expression for the row `offset` rows after
+    pub fn lead(expression: Expression, offset: i64) -> Self {
+        WindowFunction::new("lead", vec![expression, Expression::literal(offset)])
+    }
+
+    /// Compute the function over a window
+    pub fn over(self, window: Window) -> Expression {
+        over(self.call.into_fragment(), window)
+    }
+
+    /// Compute the function over a window of the `WINDOW` clause
+    pub fn over_named(self, name: &str) -> Expression {
+        over_named(self.call.into_fragment(), name)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::prelude::Order;
+
+    #[test]
+    fn window_with_params() {
+        let window = Window::extend("w")
+            .partition_by(Expression::column("a").operate("%", Expression::param(2)))
+            .order_by(Ordering::desc(
+                Expression::column("b").operate("<->", Expression::param(3)),
+            ))
+            .range(FrameBound::UnboundedPreceding, FrameBound::Following(1));
+        let expression = WindowFunction::lead(Expression::param(1), 2).over(window);
+        assert_eq!(
+            expression.get_query(),
+            "lead($1, 2) OVER (w PARTITION BY a % $2 ORDER BY b <-> $3 DESC RANGE BETWEEN UNBOUNDED PRECEDING AND 1 FOLLOWING)"
+        );
+        assert_eq!(expression.get_params().len(), 3);
+    }
+
+    #[test]
+    fn window_with_raw_strings() {
+        let mut params = Bucket::new();
+        params.push(5);
+        let window = Window::new()
+            .partition_by("a % $1")
+            .order_by(Order::Asc("abs(b - $1)".into()));
+        let expression = WindowFunction::rank().over(window);
+        assert_eq!(
+            params.merge(expression.get_query().as_str(), expression.get_params()),
+            "rank() OVER (PARTITION BY a % $1 ORDER BY abs(b - $1) ASC)"
+        );
+    }
+
+    #[test]
+    fn window_mixing_raw_strings_and_params() {
+        let mut params = Bucket::new();
+        params.push(5);
+        let window = Window::new()
+            .partition_by("a % $1")
+            .order_by(Ordering::asc(Expression::param(3)));
+        assert_eq!(window.to_string(), "PARTITION BY a % $1 ORDER BY $1 ASC");
+        let expression = WindowFunction::lag(Expression::param(4), 1).over(window);
+        assert_eq!(
+            expression.merge_into(&mut params),
+            "lag($2, 1) OVER (PARTITION BY a % $1 ORDER BY $3 ASC)"
+        );
+        assert_eq!(params.len(), 3);
+    }
+
+    #[test]
+    fn window_frames() {
+        let window = Window::new()
+            .order_by(Order::Asc("a".into()))
+            .groups(FrameBound::CurrentRow, FrameBound::UnboundedFollowing);
+        assert_eq!(
+            WindowFunction::dense_rank().over(window).get_query(),
+            "dense_rank() OVER (ORDER BY a ASC GROUPS BETWEEN CURRENT ROW AND UNBOUNDED FOLLOWING)"
+        );
+    }
+}
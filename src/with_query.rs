@@ -0,0 +1,241 @@
+use crate::bucket::{remove_placeholders, Bucket};
+use crate::prelude::{ModifyingQuery, SelectQuery};
+use std::fmt;
+use std::ops::Range;
+
+pub enum Materialization {
+    Materialized,
+    NotMaterialized,
+}
+
+impl fmt::Display for Materialization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Materialization::Materialized => write!(f, "MATERIALIZED"),
+            Materialization::NotMaterialized => write!(f, "NOT MATERIALIZED"),
+        }
+    }
+}
+
+/// A query of a `WITH` clause carrying its own parameters
+///
+/// The placeholders of the query are numbered from `$1` against its own
+/// parameters and are shifted when added to a builder.
+///
+/// # Examples
+///
+/// ```
+/// use postgres_querybuilder::{SelectBuilder, WithQuery};
+/// use postgres_querybuilder::prelude::{QueryBuilder, QueryBuilderWithQueries};
+///
+/// let mut builder = SelectBuilder::new("counts");
+/// builder.with(WithQuery::new("counts", "SELECT count(*) FROM users").materialized());
+///
+/// assert_eq!(
+///     builder.get_query(),
+///     "WITH counts AS MATERIALIZED (SELECT count(*) FROM users) SELECT * FROM counts"
+/// );
+/// ```
+pub struct WithQuery {
+    name: String,
+    columns: Vec<String>,
+    query: String,
+    recursive: bool,
+    materialization: Option<Materialization>,
+    params: Bucket,
+}
+
+impl WithQuery {
+    /// Create a `WITH` query from a raw query without parameters
+    pub fn new(name: &str, query: &str) -> Self {
+        WithQuery::with_params(name, query, Bucket::new())
+    }
+
+    fn with_params(name: &str, query: &str, params: Bucket) -> Self {
+        WithQuery {
+            name: name.into(),
+            columns: vec![],
+            query: query.into(),
+            recursive: false,
+            materialization: None,
+            params,
+        }
+    }
+
+    /// Create a `WITH` query from a select query
+    pub fn select<Q: SelectQuery>(name: &str, query: Q) -> Self {
+        let subquery = query.get_query();
+        WithQuery::with_params(name, subquery.as_str(), query.get_params())
+    }
+
+    /// Create a `WITH` query from an insert, update or delete query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{SelectBuilder, UpdateBuilder, WithQuery};
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut updated = UpdateBuilder::new("users");
+    /// updated.set("active", false);
+    /// updated.where_lt("last_login", "2020-01-01".to_string());
+    /// updated.returning("id");
+    /// let mut builder = SelectBuilder::new("updated");
+    /// builder.select("count(*)");
+    /// builder.with(WithQuery::modifying("updated", updated));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "WITH updated AS (UPDATE users SET active = $1 WHERE last_login < $2 RETURNING id) SELECT count(*) FROM updated"
+    /// );
+    /// ```
+    pub fn modifying<Q: ModifyingQuery>(name: &str, query: Q) -> Self {
+        let subquery = query.get_query();
+        WithQuery::with_params(name, subquery.as_str(), query.get_params())
+    }
+
+    /// Create a recursive `WITH` query from a non recursive select query
+    /// and a recursive one, each wrapped in parentheses and combined with
+    /// `UNION ALL`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postgres_querybuilder::{SelectBuilder, WithQuery};
+    /// use postgres_querybuilder::prelude::*;
+    ///
+    /// let mut base = SelectBuilder::new("categories");
+    /// base.select("id");
+    /// base.where_eq("id", 42);
+    /// let mut step = SelectBuilder::new("categories");
+    /// step.select("categories.id");
+    /// step.inner_join("tree", "categories.parent_id = tree.id");
+    /// let mut builder = SelectBuilder::new("tree");
+    /// builder.with(WithQuery::recursive_select("tree", base, step).columns(&["id"]));
+    ///
+    /// assert_eq!(
+    ///     builder.get_query(),
+    ///     "WITH RECURSIVE tree(id) AS ((SELECT id FROM categories WHERE id = $1) UNION ALL (SELECT categories.id FROM categories INNER JOIN tree ON categories.parent_id = tree.id)) SELECT * FROM tree"
+    /// );
+    /// ```
+    pub fn recursive_select<B: SelectQuery, S: SelectQuery>(name: &str, base: B, step: S) -> Self {
+        let base_query = base.get_query();
+        let step_query = step.get_query();
+        let mut params = base.get_params();
+        let step_query = params.merge(step_query.as_str(), step.get_params());
+        let query = format!("({}) UNION ALL ({})", base_query, step_query);
+        WithQuery::with_params(name, query.as_str(), params).recursive()
+    }
+
+    /// Set the column names of the query
+    pub fn columns(mut self, columns: &[&str]) -> Self {
+        self.columns = columns.iter().map(|column| column.to_string()).collect();
+        self
+    }
+
+    /// Allow the query to refer to its own output
+    pub fn recursive(mut self) -> Self {
+        self.recursive = true;
+        self
+    }
+
+    /// Force the query to be computed only once
+    pub fn materialized(mut self) -> Self {
+        self.materialization = Some(Materialization::Materialized);
+        self
+    }
+
+    /// Allow the query to be folded into the parent query
+    pub fn not_materialized(mut self) -> Self {
+        self.materialization = Some(Materialization::NotMaterialized);
+        self
+    }
+
+    /// Move the parameters of the query into a bucket
+    pub(crate) fn merge_into(self, params: &mut Bucket) -> Self {
+        let query = params.merge(self.query.as_str(), self.params);
+        WithQuery {
+            query,
+            params: Bucket::new(),
+            ..self
+        }
+    }
+
+    /// Renumber the placeholders following a range of removed parameters
+    pub(crate) fn remove_params(&mut self, range: &Range<usize>) {
+        self.query = remove_placeholders(self.query.as_str(), range);
+    }
+}
+
+impl fmt::Display for WithQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.columns.is_empty() {
+            write!(f, "({})", self.columns.join(", "))?;
+        }
+        write!(f, " AS ")?;
+        if let Some(materialization) = self.materialization.as_ref() {
+            write!(f, "{} ", materialization)?;
+        }
+        write!(f, "({})", self.query)
+    }
+}
+
+/// Build the `WITH` clause of a list of queries
+pub(crate) fn with_clause(queries: &[WithQuery]) -> Option<String> {
+    if !queries.is_empty() {
+        let result: Vec<String> = queries.iter().map(|item| item.to_string()).collect();
+        let recursive = queries.iter().any(|item| item.recursive);
+        let keyword = if recursive { "WITH RECURSIVE" } else { "WITH" };
+        Some(format!("{} {}", keyword, result.join(", ")))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn with_columns_and_materialization() {
+        let queries = vec![
+            WithQuery::new("a", "SELECT 1")
+                .columns(&["x"])
+                .not_materialized(),
+            WithQuery::new("b", "SELECT x FROM a").materialized(),
+        ];
+        assert_eq!(
+            with_clause(&queries).unwrap(),
+            "WITH a(x) AS NOT MATERIALIZED (SELECT 1), b AS MATERIALIZED (SELECT x FROM a)"
+        );
+    }
+
+    #[test]
+    fn with_recursive() {
+        let queries = vec![
+            WithQuery::new("a", "SELECT 1"),
+            WithQuery::new("b", "SELECT 1 UNION ALL SELECT n + 1 FROM b WHERE n < 10")
+                .columns(&["n"])
+                .recursive(),
+        ];
+        assert_eq!(
+            with_clause(&queries).unwrap(),
+            "WITH RECURSIVE a AS (SELECT 1), b(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM b WHERE n < 10)"
+        );
+    }
+
+    #[test]
+    fn raw_queries_keep_placeholders() {
+        let mut params = Bucket::new();
+        params.push(5);
+        let query = WithQuery::new("x", "SELECT * FROM t WHERE a = $1").merge_into(&mut params);
+        assert_eq!(query.to_string(), "x AS (SELECT * FROM t WHERE a = $1)");
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn without_queries() {
+        assert_eq!(with_clause(&[]), None);
+    }
+}